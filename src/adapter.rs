@@ -0,0 +1,382 @@
+//! Adapters to use embedded-hal style peripherals as Interface and Clock.
+//!
+//! `embedded-io` feature provides EmbeddedIoInterface and `embedded-hal-nb` feature provides NbInterface.
+//! HalfDuplexInterface wraps any Interface to drive the direction pin of RS-485 transceivers.
+
+use crate::utils::calc_tx_time_per_byte;
+use crate::Clock;
+use crate::Interface;
+use core::num::NonZeroU32;
+use core::time::Duration;
+
+/// Interface for half-duplex RS-485 transceivers (e.g. MAX485) whose DE and RE pins are driven by a GPIO.
+/// set_direction(true) enables the driver, e.g. |tx| if tx { de.set_high() } else { de.set_low() }.
+pub struct HalfDuplexInterface<'a, I, F>
+where
+    I: Interface,
+    F: FnMut(bool),
+{
+    uart: I,
+    clock: &'a dyn Clock,
+    set_direction: F,
+    tx_time_per_byte: u64,
+}
+
+impl<'a, I, F> HalfDuplexInterface<'a, I, F>
+where
+    I: Interface,
+    F: FnMut(bool),
+{
+    /// baudrate: Same as DynamixelControl to wait for the last stop bit.
+    pub fn new(uart: I, clock: &'a dyn Clock, mut set_direction: F, baudrate: u32) -> Self {
+        set_direction(false);
+        Self {
+            uart,
+            clock,
+            set_direction,
+            tx_time_per_byte: calc_tx_time_per_byte(baudrate),
+        }
+    }
+    pub fn release(self) -> I {
+        self.uart
+    }
+    /// A byte is 10 bits with the start and stop bits.
+    fn frame_time(&self, len: usize) -> Duration {
+        Duration::from_micros(self.tx_time_per_byte * len as u64 * 10 / 8)
+    }
+}
+
+impl<'a, I, F> Interface for HalfDuplexInterface<'a, I, F>
+where
+    I: Interface,
+    F: FnMut(bool),
+{
+    fn write_byte(&mut self, data: u8) {
+        self.write_bytes(&[data]);
+    }
+    /// Release the bus right after the last stop bit, so the status packet is not clipped.
+    fn write_bytes(&mut self, data: &[u8]) {
+        let start = self.clock.get_current_time();
+        (self.set_direction)(true);
+        self.uart.write_bytes(data);
+        let end = start + self.frame_time(data.len());
+        while self.clock.get_current_time() < end {}
+        (self.set_direction)(false);
+    }
+    fn read_byte(&mut self) -> Option<u8> {
+        self.uart.read_byte()
+    }
+    fn read_bytes(&mut self, buf: &mut [u8]) -> Option<usize> {
+        self.uart.read_bytes(buf)
+    }
+    fn clear_read_buf(&mut self) {
+        self.uart.clear_read_buf();
+    }
+}
+
+/// Interface over embedded_io serial.
+/// ReadReady is needed because DynamixelControl polls without blocking.
+#[cfg(feature = "embedded-io")]
+pub struct EmbeddedIoInterface<T> {
+    serial: T,
+}
+
+#[cfg(feature = "embedded-io")]
+impl<T> EmbeddedIoInterface<T>
+where
+    T: embedded_io::Read + embedded_io::Write + embedded_io::ReadReady,
+{
+    pub fn new(serial: T) -> Self {
+        Self { serial }
+    }
+    pub fn release(self) -> T {
+        self.serial
+    }
+}
+
+#[cfg(feature = "embedded-io")]
+impl<T> Interface for EmbeddedIoInterface<T>
+where
+    T: embedded_io::Read + embedded_io::Write + embedded_io::ReadReady,
+{
+    fn write_byte(&mut self, data: u8) {
+        self.write_bytes(&[data]);
+    }
+    fn write_bytes(&mut self, data: &[u8]) {
+        let _ = self.serial.write_all(data);
+        let _ = self.serial.flush();
+    }
+    fn read_byte(&mut self) -> Option<u8> {
+        let mut buf = [0u8; 1];
+        match self.read_bytes(&mut buf) {
+            Some(1) => Some(buf[0]),
+            _ => None,
+        }
+    }
+    fn read_bytes(&mut self, buf: &mut [u8]) -> Option<usize> {
+        match self.serial.read_ready() {
+            Ok(true) => self.serial.read(buf).ok(),
+            _ => None,
+        }
+    }
+    fn clear_read_buf(&mut self) {
+        let mut buf = [0u8; 16];
+        while let Ok(true) = self.serial.read_ready() {
+            match self.serial.read(&mut buf) {
+                Ok(0) | Err(_) => break,
+                Ok(_) => {}
+            }
+        }
+    }
+}
+
+/// Interface over embedded-hal-nb serial.
+#[cfg(feature = "embedded-hal-nb")]
+pub struct NbInterface<T> {
+    serial: T,
+}
+
+#[cfg(feature = "embedded-hal-nb")]
+impl<T> NbInterface<T>
+where
+    T: embedded_hal_nb::serial::Read + embedded_hal_nb::serial::Write,
+{
+    pub fn new(serial: T) -> Self {
+        Self { serial }
+    }
+    pub fn release(self) -> T {
+        self.serial
+    }
+}
+
+#[cfg(feature = "embedded-hal-nb")]
+impl<T> Interface for NbInterface<T>
+where
+    T: embedded_hal_nb::serial::Read + embedded_hal_nb::serial::Write,
+{
+    fn write_byte(&mut self, data: u8) {
+        let _ = embedded_hal_nb::nb::block!(self.serial.write(data));
+    }
+    fn write_bytes(&mut self, data: &[u8]) {
+        for d in data {
+            let _ = embedded_hal_nb::nb::block!(self.serial.write(*d));
+        }
+        let _ = embedded_hal_nb::nb::block!(self.serial.flush());
+    }
+    fn read_byte(&mut self) -> Option<u8> {
+        self.serial.read().ok()
+    }
+    fn read_bytes(&mut self, buf: &mut [u8]) -> Option<usize> {
+        let mut len = 0;
+        while len < buf.len() {
+            match self.serial.read() {
+                Ok(v) => buf[len] = v,
+                Err(_) => break,
+            }
+            len += 1;
+        }
+        Some(len)
+    }
+    fn clear_read_buf(&mut self) {
+        while self.serial.read().is_ok() {}
+    }
+}
+
+/// Clock over a monotonic timer,
+/// e.g. MonotonicClock::new(|| timer.now().ticks(), NonZeroU32::new(1_000_000).unwrap()).
+pub struct MonotonicClock<F>
+where
+    F: Fn() -> u64,
+{
+    now: F,
+    tick_hz: NonZeroU32,
+}
+
+impl<F> MonotonicClock<F>
+where
+    F: Fn() -> u64,
+{
+    /// now: Ticks of the timer
+    /// tick_hz: Ticks per second
+    pub fn new(now: F, tick_hz: NonZeroU32) -> Self {
+        Self { now, tick_hz }
+    }
+}
+
+impl<F> Clock for MonotonicClock<F>
+where
+    F: Fn() -> u64,
+{
+    fn get_current_time(&self) -> Duration {
+        let ticks = (self.now)();
+        let hz = self.tick_hz.get() as u64;
+        let nanos = (ticks % hz) * 1_000_000_000 / hz;
+        Duration::new(ticks / hz, nanos as u32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::adapter::HalfDuplexInterface;
+    use crate::adapter::MonotonicClock;
+    use crate::Clock;
+    use crate::Interface;
+    use core::cell::Cell;
+    use core::cell::RefCell;
+    use core::num::NonZeroU32;
+    use core::time::Duration;
+    use heapless::Vec;
+
+    #[test]
+    fn half_duplex_interface() {
+        struct MockSerial<'a> {
+            clock: &'a Cell<u64>,
+            written_at: Option<u64>,
+        }
+        impl<'a> Interface for MockSerial<'a> {
+            fn write_byte(&mut self, _data: u8) {}
+            fn write_bytes(&mut self, _data: &[u8]) {
+                self.written_at = Some(self.clock.get());
+            }
+            fn read_byte(&mut self) -> Option<u8> {
+                None
+            }
+            fn read_bytes(&mut self, _buf: &mut [u8]) -> Option<usize> {
+                None
+            }
+            fn clear_read_buf(&mut self) {}
+        }
+
+        // Advances 1 [usec] every call
+        let ticks = Cell::new(0u64);
+        let clock = MonotonicClock::new(
+            || {
+                ticks.set(ticks.get() + 1);
+                ticks.get()
+            },
+            NonZeroU32::new(1_000_000).unwrap(),
+        );
+        let directions = RefCell::new(Vec::<(bool, u64), 8>::new());
+        let uart = MockSerial {
+            clock: &ticks,
+            written_at: None,
+        };
+        let mut interface = HalfDuplexInterface::new(
+            uart,
+            &clock,
+            |tx| directions.borrow_mut().push((tx, ticks.get())).unwrap(),
+            1_000_000,
+        );
+        // Started at 1 [usec] and 14 bytes take 14 * 10 [usec] at 1Mbps
+        interface.write_bytes(&[0u8; 14]);
+        let uart = interface.release();
+        let directions = directions.borrow();
+        assert_eq!(directions[0], (false, 0));
+        assert!(directions[1].0);
+        assert!(directions[1].1 <= uart.written_at.unwrap());
+        assert_eq!(directions[2], (false, 141));
+    }
+
+    #[test]
+    fn monotonic_clock() {
+        let ticks = Cell::new(0u64);
+        let clock = MonotonicClock::new(|| ticks.get(), NonZeroU32::new(32_768).unwrap());
+        assert_eq!(clock.get_current_time(), Duration::new(0, 0));
+        ticks.set(32_768 * 3 + 16_384);
+        assert_eq!(clock.get_current_time(), Duration::from_millis(3500));
+        // Does not overflow with 64bit timer
+        ticks.set(u64::MAX);
+        assert_eq!(clock.get_current_time().as_secs(), u64::MAX / 32_768);
+    }
+
+    #[cfg(feature = "embedded-io")]
+    #[test]
+    fn embedded_io_interface() {
+        use crate::adapter::EmbeddedIoInterface;
+        use crate::Interface;
+        use heapless::Deque;
+
+        struct Loopback {
+            buf: Deque<u8, 32>,
+        }
+        impl embedded_io::ErrorType for Loopback {
+            type Error = core::convert::Infallible;
+        }
+        impl embedded_io::Read for Loopback {
+            fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+                let m = core::cmp::min(self.buf.len(), buf.len());
+                for b in buf.iter_mut().take(m) {
+                    *b = self.buf.pop_front().unwrap();
+                }
+                Ok(m)
+            }
+        }
+        impl embedded_io::ReadReady for Loopback {
+            fn read_ready(&mut self) -> Result<bool, Self::Error> {
+                Ok(!self.buf.is_empty())
+            }
+        }
+        impl embedded_io::Write for Loopback {
+            fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+                for b in buf {
+                    self.buf.push_back(*b).unwrap();
+                }
+                Ok(buf.len())
+            }
+            fn flush(&mut self) -> Result<(), Self::Error> {
+                Ok(())
+            }
+        }
+
+        let mut interface = EmbeddedIoInterface::new(Loopback { buf: Deque::new() });
+        let mut buf = [0u8; 4];
+        assert_eq!(interface.read_bytes(&mut buf), None);
+        interface.write_bytes(&[0xFF, 0xFF, 0xFD]);
+        assert_eq!(interface.read_byte(), Some(0xFF));
+        assert_eq!(interface.read_bytes(&mut buf), Some(2));
+        assert_eq!(buf[..2], [0xFF, 0xFD]);
+        interface.write_bytes(&[0x00, 0x01]);
+        interface.clear_read_buf();
+        assert_eq!(interface.read_byte(), None);
+    }
+
+    #[cfg(feature = "embedded-hal-nb")]
+    #[test]
+    fn nb_interface() {
+        use crate::adapter::NbInterface;
+        use crate::Interface;
+        use embedded_hal_nb::nb;
+        use heapless::Deque;
+
+        struct Loopback {
+            buf: Deque<u8, 32>,
+        }
+        impl embedded_hal_nb::serial::ErrorType for Loopback {
+            type Error = embedded_hal_nb::serial::ErrorKind;
+        }
+        impl embedded_hal_nb::serial::Read for Loopback {
+            fn read(&mut self) -> nb::Result<u8, Self::Error> {
+                self.buf.pop_front().ok_or(nb::Error::WouldBlock)
+            }
+        }
+        impl embedded_hal_nb::serial::Write for Loopback {
+            fn write(&mut self, word: u8) -> nb::Result<(), Self::Error> {
+                self.buf.push_back(word).unwrap();
+                Ok(())
+            }
+            fn flush(&mut self) -> nb::Result<(), Self::Error> {
+                Ok(())
+            }
+        }
+
+        let mut interface = NbInterface::new(Loopback { buf: Deque::new() });
+        let mut buf = [0u8; 4];
+        assert_eq!(interface.read_byte(), None);
+        interface.write_bytes(&[0xFF, 0xFF, 0xFD]);
+        assert_eq!(interface.read_bytes(&mut buf), Some(3));
+        assert_eq!(buf[..3], [0xFF, 0xFF, 0xFD]);
+        interface.write_byte(0x00);
+        interface.clear_read_buf();
+        assert_eq!(interface.read_bytes(&mut buf), Some(0));
+    }
+}
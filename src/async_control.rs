@@ -0,0 +1,1089 @@
+//! Async version of DynamixelControl, enabled by the `async` feature.
+//!
+//! The serial port is embedded_io_async::Read + Write and the timeout is embedded_hal_async::delay::DelayNs,
+//! so other tasks of the executor (e.g. Embassy, Tokio) run while waiting for the status packets.
+//! Packets are built and checked by the same code as DynamixelControl, so only the I/O differs.
+
+use crate::packet_handler::check_echo;
+use crate::packet_handler::find_status_packet;
+use crate::packet_handler::new_multi_read_result;
+use crate::packet_handler::parse_fast_read_status;
+use crate::packet_handler::parse_multi_read_status;
+use crate::packet_handler::parse_status_packet;
+use crate::packet_handler::remove_stuffing;
+use crate::packet_handler::status_packet_len;
+use crate::packet_handler::MultiReadResult;
+use crate::packet_handler::MAX_ID;
+use crate::packet_handler::MAX_PACKET_LEN;
+use crate::packet_handler::MAX_READ_ID_NUM;
+use crate::protocol1::find_protocol1_status_packet;
+use crate::registry::check_limits;
+use crate::registry::DeviceRegistry;
+use crate::utils::calc_tx_time_per_byte;
+use crate::CommunicationResult;
+use crate::ControlTable;
+use crate::DeviceLimits;
+use crate::DynamixelModel;
+use crate::HardwareErrorStatus;
+use crate::Instruction;
+use crate::ProtocolVersion;
+use core::future::poll_fn;
+use core::future::Future;
+use core::pin::pin;
+use core::result::Result;
+use core::task::Poll;
+use embedded_hal_async::delay::DelayNs;
+use embedded_io_async::{Read, Write};
+use heapless::Vec;
+
+pub struct AsyncDynamixelControl<U, D>
+where
+    U: Read + Write,
+    D: DelayNs,
+{
+    uart: U,
+    delay: D,
+    tx_time_per_byte: u64,
+    registry: DeviceRegistry,
+    echo_suppression: bool,
+}
+
+impl<U, D> AsyncDynamixelControl<U, D>
+where
+    U: Read + Write,
+    D: DelayNs,
+{
+    pub fn new(uart: U, delay: D, baudrate: u32) -> Self {
+        Self {
+            uart,
+            delay,
+            tx_time_per_byte: calc_tx_time_per_byte(baudrate),
+            registry: DeviceRegistry::new(),
+            echo_suppression: false,
+        }
+    }
+
+    pub fn release(self) -> (U, D) {
+        (self.uart, self.delay)
+    }
+
+    /// Protocol 2.0 is used by default.
+    /// This is used for the broadcast and the IDs not registered by set_id_protocol_version.
+    pub fn set_protocol_version(&mut self, version: ProtocolVersion) {
+        self.registry.protocol_version = version;
+    }
+
+    pub fn get_protocol_version(&self) -> ProtocolVersion {
+        self.registry.protocol_version
+    }
+
+    /// Register the protocol version of the device, so both versions can share the bus.
+    pub fn set_id_protocol_version(
+        &mut self,
+        id: u8,
+        version: ProtocolVersion,
+    ) -> Result<(), CommunicationResult> {
+        self.registry.set_id_protocol_version(id, version)
+    }
+
+    pub fn get_id_protocol_version(&self, id: u8) -> ProtocolVersion {
+        self.registry.get_id_protocol_version(id)
+    }
+
+    /// Register the model of the device to resolve its control table.
+    pub fn set_model(&mut self, id: u8, model: DynamixelModel) -> Result<(), CommunicationResult> {
+        self.registry.set_model(id, model)
+    }
+
+    /// DynamixelModel::default() is used for the IDs not registered by set_model.
+    pub fn get_model(&self, id: u8) -> DynamixelModel {
+        self.registry.get_model(id)
+    }
+
+    /// Validate the written value against the range of the item and the limits of the device before sending.
+    /// Disabled by default, because the limits are read from the device on the first write.
+    pub fn set_range_check(&mut self, enable: bool) {
+        self.registry.range_check = enable;
+    }
+
+    /// For single-wire TTL buses where TX and RX are tied together.
+    /// The transmitted bytes are read back and checked before receiving the status packet.
+    pub fn set_echo_suppression(&mut self, enable: bool) {
+        self.echo_suppression = enable;
+    }
+
+    /// Read the limit registers from the device and cache them.
+    pub async fn read_limits(&mut self, id: u8) -> Result<DeviceLimits, CommunicationResult> {
+        if id > MAX_ID {
+            return Err(CommunicationResult::NotAvailable);
+        }
+        let mut limits = DeviceLimits::default();
+        let operating_mode = self.read_optional(id, ControlTable::OperatingMode).await?;
+        if operating_mode == Some(3) {
+            limits.max_position = self
+                .read_optional(id, ControlTable::MaxPositionLimit)
+                .await?;
+            limits.min_position = self
+                .read_optional(id, ControlTable::MinPositionLimit)
+                .await?;
+        }
+        limits.current = self.read_optional(id, ControlTable::CurrentLimit).await?;
+        limits.velocity = self.read_optional(id, ControlTable::VelocityLimit).await?;
+        limits.pwm = self.read_optional(id, ControlTable::PWMLimit).await?;
+        self.registry.cache_limits(id, limits);
+        Ok(limits)
+    }
+
+    /// Limits cached by read_limits.
+    pub fn get_limits(&self, id: u8) -> Option<DeviceLimits> {
+        self.registry.get_limits(id)
+    }
+
+    /// The cache is also cleared by writing EEPROM area of the device.
+    pub fn clear_limits(&mut self, id: u8) {
+        self.registry.clear_limits(id)
+    }
+
+    /// None if the device does not have the item.
+    async fn read_optional(
+        &mut self,
+        id: u8,
+        data_name: ControlTable,
+    ) -> Result<Option<i32>, CommunicationResult> {
+        let result = match data_name.to_size() {
+            1 => self.read_1byte(id, data_name).await.map(|v| v as i32),
+            2 => self.read_2byte(id, data_name).await.map(|v| v as i32),
+            _ => self.read_4byte(id, data_name).await.map(|v| v as i32),
+        };
+        match result {
+            Ok(v) => Ok(Some(v)),
+            Err(CommunicationResult::NotAvailable) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Validate the value enabled by set_range_check.
+    /// The limits of the device are read when they are not cached.
+    async fn check_range(
+        &mut self,
+        id: u8,
+        data_name: ControlTable,
+        data: &[u8],
+    ) -> Result<(), CommunicationResult> {
+        let value = match self.registry.check_range(id, data_name, data)? {
+            Some(v) => v,
+            None => return Ok(()),
+        };
+        let limits = match self.get_limits(id) {
+            Some(v) => v,
+            None => self.read_limits(id).await?,
+        };
+        check_limits(data_name, value, &limits)
+    }
+
+    /// Drop the bytes left from the previous transaction, e.g. the status packet arriving after timeout.
+    async fn clear_port(&mut self) {
+        let mut res = [0u8; MAX_PACKET_LEN];
+        let uart = &mut self.uart;
+        while let Some(Ok(readlen)) = with_timeout(&mut self.delay, 0, uart.read(&mut res)).await {
+            if readlen == 0 {
+                break;
+            }
+        }
+    }
+
+    /// Send the finished packet.
+    async fn send_packet(&mut self, msg: &[u8]) -> Result<(), CommunicationResult> {
+        self.clear_port().await;
+        match self.uart.write_all(msg).await {
+            Ok(_) => {}
+            Err(_) => return Err(CommunicationResult::TxFail),
+        }
+        match self.uart.flush().await {
+            Ok(_) => {}
+            Err(_) => return Err(CommunicationResult::TxFail),
+        }
+        self.receive_echo(msg).await
+    }
+
+    /// Read back the transmitted bytes enabled by set_echo_suppression.
+    async fn receive_echo(&mut self, msg: &[u8]) -> Result<(), CommunicationResult> {
+        if !self.echo_suppression {
+            return Ok(());
+        }
+        let timeout_usec = self.timeout_usec(msg.len());
+        let uart = &mut self.uart;
+        let received = with_timeout(&mut self.delay, timeout_usec, async {
+            let mut echo = Vec::<u8, MAX_PACKET_LEN>::new();
+            let mut res = [0u8; MAX_PACKET_LEN];
+            while echo.len() < msg.len() {
+                match uart.read(&mut res[..msg.len() - echo.len()]).await {
+                    Ok(0) | Err(_) => return Err(CommunicationResult::RxFail),
+                    Ok(readlen) => echo.extend(res[0..readlen].iter().cloned()),
+                }
+                check_echo(msg, &echo)?;
+            }
+            Ok(())
+        })
+        .await;
+
+        match received {
+            Some(v) => v,
+            None => Err(CommunicationResult::RxTimeout),
+        }
+    }
+
+    fn timeout_usec(&self, packet_length: usize) -> u32 {
+        const LATENCY_CLOCK: u64 = 1_000; // usec
+        ((self.tx_time_per_byte * packet_length as u64) + (LATENCY_CLOCK * 2) + 2_000) as u32
+    }
+
+    /// Wait for the status packet while the timeout calculated from packet_length.
+    async fn receive_packet(
+        &mut self,
+        version: ProtocolVersion,
+        packet_length: usize,
+    ) -> Result<Vec<u8, MAX_PACKET_LEN>, CommunicationResult> {
+        let timeout_usec = self.timeout_usec(packet_length);
+        let mut wait_length = status_packet_len(version, 0); // minimum length
+        let mut msg = Vec::<u8, MAX_PACKET_LEN>::new();
+        let uart = &mut self.uart;
+        let received = with_timeout(&mut self.delay, timeout_usec, async {
+            let mut res = [0u8; MAX_PACKET_LEN];
+            loop {
+                let found = match version {
+                    ProtocolVersion::V1 => find_protocol1_status_packet(&mut msg, &mut wait_length),
+                    ProtocolVersion::V2 => find_status_packet(&mut msg, &mut wait_length),
+                };
+                match found {
+                    Ok(true) => return Ok(()),
+                    Ok(false) => {}
+                    Err(e) => return Err(e),
+                }
+                match uart.read(&mut res[..wait_length - msg.len()]).await {
+                    Ok(0) | Err(_) => return Err(CommunicationResult::RxFail),
+                    Ok(readlen) => msg.extend(res[0..readlen].iter().cloned()),
+                }
+            }
+        })
+        .await;
+
+        let result = match received {
+            Some(v) => v,
+            None if msg.is_empty() => Err(CommunicationResult::RxTimeout),
+            None => Err(CommunicationResult::RxCorrupt),
+        };
+        match result {
+            Ok(_) => {
+                if version == ProtocolVersion::V2 {
+                    remove_stuffing(&mut msg);
+                }
+                Ok(msg)
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Receive the status packet from the device and return its parameters.
+    /// When the device reports alert, its Hardware Error Status is read.
+    async fn receive_status_packet(
+        &mut self,
+        id: u8,
+        data_length: u16,
+    ) -> Result<Vec<u8, MAX_PACKET_LEN>, CommunicationResult> {
+        match self
+            .receive_checked_status_packet(id, data_length, false)
+            .await
+        {
+            Err(e) => Err(self.diagnose_alert(id, e).await),
+            Ok(v) => Ok(v),
+        }
+    }
+
+    async fn receive_checked_status_packet(
+        &mut self,
+        id: u8,
+        data_length: u16,
+        ignore_alert: bool,
+    ) -> Result<Vec<u8, MAX_PACKET_LEN>, CommunicationResult> {
+        let version = self.get_id_protocol_version(id);
+        let status = self
+            .receive_packet(version, status_packet_len(version, data_length))
+            .await?;
+        parse_status_packet(version, &status, id, data_length, ignore_alert)
+    }
+
+    /// Replace the status error with alert by the Hardware Error Status of the device.
+    async fn diagnose_alert(&mut self, id: u8, error: CommunicationResult) -> CommunicationResult {
+        match error {
+            CommunicationResult::StatusError { alert: true, .. } => {
+                match self.get_hardware_error_status(id).await {
+                    Ok(status) => CommunicationResult::HardwareError { id, status },
+                    Err(_) => error,
+                }
+            }
+            _ => error,
+        }
+    }
+
+    async fn diagnose_multi_read_alert(&mut self, result: &mut MultiReadResult) {
+        for (id, data) in result.iter_mut() {
+            if let Err(e) = data {
+                *e = self.diagnose_alert(*id, *e).await;
+            }
+        }
+    }
+
+    /// Status of the device always has alert while hardware error exists.
+    pub async fn get_hardware_error_status(
+        &mut self,
+        id: u8,
+    ) -> Result<HardwareErrorStatus, CommunicationResult> {
+        if self.get_id_protocol_version(id) == ProtocolVersion::V1 {
+            return Err(CommunicationResult::NotAvailable);
+        }
+        let msg = self
+            .registry
+            .read_packet(id, ControlTable::HardwareErrorStatus, 1)?;
+        match self.send_packet(&msg).await {
+            Ok(_) => {}
+            Err(e) => return Err(e),
+        }
+        match self.receive_checked_status_packet(id, 1, true).await {
+            Ok(v) => Ok(HardwareErrorStatus::from_value(v[0])),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Register the model of the device when it is known.
+    pub async fn ping(&mut self, id: u8) -> Result<(u16, u8), CommunicationResult> {
+        let msg = self.registry.ping_packet(id)?;
+        match self.send_packet(&msg).await {
+            Ok(_) => {}
+            Err(e) => return Err(e),
+        }
+
+        let data = match self.get_id_protocol_version(id) {
+            // Protocol 1.0 ping has no model number, so it is read after ping.
+            ProtocolVersion::V1 => {
+                self.receive_status_packet(id, 0).await?;
+                self.read(id, ControlTable::ModelNumber, 3).await?
+            }
+            ProtocolVersion::V2 => self.receive_status_packet(id, 3).await?,
+        };
+
+        // Model Number(2[byte]) + Firmware Version(1[byte])
+        let model_number = u16::from_le_bytes([data[0], data[1]]);
+        self.registry.register_model(id, model_number);
+        Ok((model_number, data[2]))
+    }
+
+    /// TxRx
+    pub async fn read(
+        &mut self,
+        id: u8,
+        data_name: ControlTable,
+        data_length: u16,
+    ) -> Result<Vec<u8, MAX_PACKET_LEN>, CommunicationResult> {
+        let msg = self.registry.read_packet(id, data_name, data_length)?;
+        match self.send_packet(&msg).await {
+            Ok(_) => {}
+            Err(e) => return Err(e),
+        }
+        self.receive_status_packet(id, data_length).await
+    }
+
+    /// Read the item as the size of Protocol 2.0 control table.
+    /// Smaller Protocol 1.0 items are zero extended.
+    async fn read_sized(
+        &mut self,
+        id: u8,
+        data_name: ControlTable,
+        data_size: u16,
+    ) -> Result<Vec<u8, MAX_PACKET_LEN>, CommunicationResult> {
+        let size = self.registry.get_read_size(id, data_name, data_size)?;
+        let mut data = self.read(id, data_name, size).await?;
+        data.resize(data_size as usize, 0x00).unwrap();
+        Ok(data)
+    }
+
+    pub async fn read_1byte(
+        &mut self,
+        id: u8,
+        data_name: ControlTable,
+    ) -> Result<u8, CommunicationResult> {
+        match self.read_sized(id, data_name, 1).await {
+            Ok(v) => Ok(u8::from_le_bytes([v[0]])),
+            Err(e) => Err(e),
+        }
+    }
+    pub async fn read_2byte(
+        &mut self,
+        id: u8,
+        data_name: ControlTable,
+    ) -> Result<u16, CommunicationResult> {
+        match self.read_sized(id, data_name, 2).await {
+            Ok(v) => Ok(u16::from_le_bytes([v[0], v[1]])),
+            Err(e) => Err(e),
+        }
+    }
+    pub async fn read_4byte(
+        &mut self,
+        id: u8,
+        data_name: ControlTable,
+    ) -> Result<u32, CommunicationResult> {
+        match self.read_sized(id, data_name, 4).await {
+            Ok(v) => Ok(u32::from_le_bytes([v[0], v[1], v[2], v[3]])),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// TxRx
+    pub async fn write(
+        &mut self,
+        id: u8,
+        data_name: ControlTable,
+        data: &[u8],
+    ) -> Result<(), CommunicationResult> {
+        let msg = self
+            .registry
+            .write_packet(Instruction::Write, id, data_name, data)?;
+        match self.check_range(id, data_name, data).await {
+            Ok(_) => {}
+            Err(e) => return Err(e),
+        }
+        match self.send_packet(&msg).await {
+            Ok(_) => {}
+            Err(e) => return Err(e),
+        }
+        self.registry
+            .cache_write(Instruction::Write, id, data_name, data);
+
+        match self.receive_status_packet(id, 0).await {
+            Ok(_) => Ok(()),
+            Err(e) => Err(e),
+        }
+    }
+    pub async fn write_1byte(
+        &mut self,
+        id: u8,
+        data_name: ControlTable,
+        data: u8,
+    ) -> Result<(), CommunicationResult> {
+        self.write(id, data_name, &[data]).await
+    }
+    pub async fn write_2byte(
+        &mut self,
+        id: u8,
+        data_name: ControlTable,
+        data: u16,
+    ) -> Result<(), CommunicationResult> {
+        self.write(id, data_name, &data.to_le_bytes()).await
+    }
+    pub async fn write_4byte(
+        &mut self,
+        id: u8,
+        data_name: ControlTable,
+        data: u32,
+    ) -> Result<(), CommunicationResult> {
+        self.write(id, data_name, &data.to_le_bytes()).await
+    }
+
+    /// Receive the status packets of the devices using the protocol.
+    /// params: (id, data length)
+    async fn receive_multi_read_packet(
+        &mut self,
+        version: ProtocolVersion,
+        params: &[(u8, u16)],
+        result: &mut MultiReadResult,
+    ) {
+        // Devices answer one after another, so each of them is waited for the longest status packet.
+        let mut status_len = 0;
+        for (_, data_length) in params {
+            status_len = status_len.max(status_packet_len(version, *data_length));
+        }
+        let mut remaining = result
+            .iter()
+            .filter(|(id, _)| self.get_id_protocol_version(*id) == version)
+            .count();
+        while remaining > 0 {
+            let status = match self.receive_packet(version, status_len).await {
+                Ok(v) => v,
+                Err(CommunicationResult::RxCRCError) => continue,
+                Err(_) => break,
+            };
+            if parse_multi_read_status(version, &status, params, result) {
+                remaining -= 1;
+            }
+        }
+    }
+
+    /// TxRx
+    pub async fn sync_read(
+        &mut self,
+        id: &[u8],
+        data_name: ControlTable,
+        data_size: u16,
+    ) -> Result<MultiReadResult, CommunicationResult> {
+        if id.len() > MAX_READ_ID_NUM {
+            return Err(CommunicationResult::NotAvailable);
+        }
+        let msg =
+            self.registry
+                .sync_read_packet(Instruction::SyncRead, id, data_name, data_size)?;
+        match self.send_packet(&msg).await {
+            Ok(_) => {}
+            Err(e) => return Err(e),
+        }
+
+        let mut lengths = Vec::<(u8, u16), MAX_READ_ID_NUM>::new();
+        for i in id {
+            lengths.push((*i, data_size)).unwrap();
+        }
+        let mut result = new_multi_read_result(&lengths);
+        self.receive_multi_read_packet(ProtocolVersion::V2, &lengths, &mut result)
+            .await;
+        self.diagnose_multi_read_alert(&mut result).await;
+        Ok(result)
+    }
+
+    /// TxRx
+    /// All devices answer in a single status packet.
+    pub async fn fast_sync_read(
+        &mut self,
+        id: &[u8],
+        data_name: ControlTable,
+        data_size: u16,
+    ) -> Result<MultiReadResult, CommunicationResult> {
+        let msg =
+            self.registry
+                .sync_read_packet(Instruction::FastSyncRead, id, data_name, data_size)?;
+        match self.send_packet(&msg).await {
+            Ok(_) => {}
+            Err(e) => return Err(e),
+        }
+
+        let mut lengths = Vec::<(u8, u16), MAX_READ_ID_NUM>::new();
+        for i in id {
+            lengths.push((*i, data_size)).unwrap();
+        }
+        Ok(self.receive_fast_read_packet(&lengths).await)
+    }
+
+    /// Receive the status packet of fast sync read and fast bulk read.
+    /// params: (id, data length)
+    async fn receive_fast_read_packet(&mut self, params: &[(u8, u16)]) -> MultiReadResult {
+        // header + id + length + instruction + (err + id + data + crc) * n
+        let mut status_len = 8;
+        for (_, data_length) in params {
+            status_len += 4 + *data_length as usize;
+        }
+        let status = self.receive_packet(ProtocolVersion::V2, status_len).await;
+        let mut result = parse_fast_read_status(status, params);
+        self.diagnose_multi_read_alert(&mut result).await;
+        result
+    }
+
+    /// No status packet is returned.
+    /// Devices with different protocols are written by separate packets.
+    pub async fn sync_write(
+        &mut self,
+        id: &[u8],
+        data: &[u8],
+        data_name: ControlTable,
+        data_size: u16,
+    ) -> Result<(), CommunicationResult> {
+        let packets = self
+            .registry
+            .sync_write_packets(id, data, data_name, data_size)?;
+        for msg in packets.iter() {
+            match self.send_packet(msg).await {
+                Ok(_) => {}
+                Err(e) => return Err(e),
+            }
+        }
+        self.registry
+            .cache_sync_torque_enable(id, data, data_name, data_size);
+
+        Ok(())
+    }
+
+    /// TxRx
+    /// Devices with different protocols are read by separate packets.
+    /// params: (id, data name, data length)
+    pub async fn bulk_read(
+        &mut self,
+        params: &[(u8, ControlTable, u16)],
+    ) -> Result<MultiReadResult, CommunicationResult> {
+        if params.len() > MAX_READ_ID_NUM {
+            return Err(CommunicationResult::NotAvailable);
+        }
+        let mut lengths = Vec::<(u8, u16), MAX_READ_ID_NUM>::new();
+        for (id, _, data_length) in params {
+            lengths.push((*id, *data_length)).unwrap();
+        }
+        let mut result = new_multi_read_result(&lengths);
+        for version in [ProtocolVersion::V2, ProtocolVersion::V1] {
+            let version_params = self.registry.get_version_params(params, version);
+            if version_params.is_empty() {
+                continue;
+            }
+            let msg = self
+                .registry
+                .bulk_read_packet(Instruction::BulkRead, &version_params)?;
+            match self.send_packet(&msg).await {
+                Ok(_) => {}
+                Err(e) => return Err(e),
+            }
+            self.receive_multi_read_packet(version, &lengths, &mut result)
+                .await;
+        }
+
+        self.diagnose_multi_read_alert(&mut result).await;
+        Ok(result)
+    }
+
+    /// TxRx
+    /// All devices answer in a single status packet.
+    /// params: (id, data name, data length)
+    pub async fn fast_bulk_read(
+        &mut self,
+        params: &[(u8, ControlTable, u16)],
+    ) -> Result<MultiReadResult, CommunicationResult> {
+        let msg = self
+            .registry
+            .bulk_read_packet(Instruction::FastBulkRead, params)?;
+        match self.send_packet(&msg).await {
+            Ok(_) => {}
+            Err(e) => return Err(e),
+        }
+
+        let mut lengths = Vec::<(u8, u16), MAX_READ_ID_NUM>::new();
+        for (id, _, data_length) in params {
+            lengths.push((*id, *data_length)).unwrap();
+        }
+        Ok(self.receive_fast_read_packet(&lengths).await)
+    }
+
+    /// No status packet is returned.
+    /// params: (id, data name, data)
+    pub async fn bulk_write(
+        &mut self,
+        params: &[(u8, ControlTable, &[u8])],
+    ) -> Result<(), CommunicationResult> {
+        let msg = self.registry.bulk_write_packet(params)?;
+        match self.send_packet(&msg).await {
+            Ok(_) => {}
+            Err(e) => return Err(e),
+        }
+        for (id, data_name, data) in params {
+            self.registry
+                .cache_write(Instruction::Write, *id, *data_name, data);
+        }
+
+        Ok(())
+    }
+}
+
+/// None when the delay finishes before fut.
+async fn with_timeout<D: DelayNs, F: Future>(
+    delay: &mut D,
+    usec: u32,
+    fut: F,
+) -> Option<F::Output> {
+    let mut fut = pin!(fut);
+    let mut timer = pin!(delay.delay_us(usec));
+    poll_fn(|cx| {
+        if let Poll::Ready(v) = fut.as_mut().poll(cx) {
+            return Poll::Ready(Some(v));
+        }
+        match timer.as_mut().poll(cx) {
+            Poll::Ready(_) => Poll::Ready(None),
+            Poll::Pending => Poll::Pending,
+        }
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::async_control::AsyncDynamixelControl;
+    use crate::CommunicationResult;
+    use crate::ControlTable;
+    use crate::DynamixelModel;
+    use crate::HardwareError;
+    use crate::ProtocolVersion;
+    use core::convert::Infallible;
+    use core::future::Future;
+    use core::pin::pin;
+    use core::task::{Context, Poll, Waker};
+    use embedded_hal_async::delay::DelayNs;
+    use embedded_io_async::{ErrorType, Read, Write};
+    use heapless::{Deque, Vec};
+
+    struct MockSerial {
+        rx_buf: Vec<u8, 256>,            // written by AsyncDynamixelControl
+        tx_buf: Deque<u8, 256>,          // status packets from the devices
+        replies: Deque<Vec<u8, 256>, 4>, // released one by one as packets are sent
+    }
+    impl MockSerial {
+        /// Each sent packet is answered by the next reply.
+        fn new(replies: &[&[u8]]) -> Self {
+            let mut queue = Deque::<Vec<u8, 256>, 4>::new();
+            for reply in replies {
+                queue.push_back(Vec::from_slice(reply).unwrap()).unwrap();
+            }
+            Self {
+                rx_buf: Vec::<u8, 256>::new(),
+                tx_buf: Deque::<u8, 256>::new(),
+                replies: queue,
+            }
+        }
+
+        /// Bytes already waiting before the next packet is sent.
+        fn push_stale(&mut self, bytes: &[u8]) {
+            for b in bytes {
+                self.tx_buf.push_back(*b).unwrap();
+            }
+        }
+    }
+    impl ErrorType for MockSerial {
+        type Error = Infallible;
+    }
+    impl Read for MockSerial {
+        async fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+            if self.tx_buf.is_empty() {
+                // No status packet comes
+                core::future::pending::<()>().await;
+            }
+            let mut len = 0;
+            while len < buf.len() {
+                match self.tx_buf.pop_front() {
+                    Some(v) => buf[len] = v,
+                    None => break,
+                }
+                len += 1;
+            }
+            Ok(len)
+        }
+    }
+    impl Write for MockSerial {
+        async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+            self.rx_buf.extend(buf.iter().cloned());
+            Ok(buf.len())
+        }
+
+        async fn flush(&mut self) -> Result<(), Self::Error> {
+            if let Some(reply) = self.replies.pop_front() {
+                self.push_stale(&reply);
+            }
+            Ok(())
+        }
+    }
+
+    /// Timeout expires as soon as the status packet is not ready.
+    struct MockDelay {}
+    impl DelayNs for MockDelay {
+        async fn delay_ns(&mut self, _ns: u32) {}
+    }
+
+    fn block_on<F: Future>(fut: F) -> F::Output {
+        let mut fut = pin!(fut);
+        let mut cx = Context::from_waker(Waker::noop());
+        loop {
+            if let Poll::Ready(v) = fut.as_mut().poll(&mut cx) {
+                return v;
+            }
+        }
+    }
+
+    #[test]
+    fn ping() {
+        let status = [
+            0xFF, 0xFF, 0xFD, 0x00, 0x01, 0x07, 0x00, 0x55, 0x00, 0x06, 0x04, 0x26, 0x65, 0x5D,
+        ];
+        let mut dxl =
+            AsyncDynamixelControl::new(MockSerial::new(&[&status]), MockDelay {}, 1_000_000);
+        assert_eq!(block_on(dxl.ping(1)), Ok((0x0406, 0x26)));
+        assert_eq!(dxl.get_model(1), DynamixelModel::Xm430W210);
+        let (uart, _) = dxl.release();
+        assert_eq!(
+            uart.rx_buf[..],
+            [0xFF, 0xFF, 0xFD, 0x00, 0x01, 0x03, 0x00, 0x01, 0x19, 0x4E]
+        );
+    }
+
+    #[test]
+    fn read() {
+        // Noise before the status packet is dropped.
+        let status = [
+            0x00, 0xFF, 0xFF, 0xFF, 0xFD, 0x00, 0x01, 0x08, 0x00, 0x55, 0x00, 0x00, 0x08, 0x00,
+            0x00, 0x1C, 0x38,
+        ];
+        let mut dxl =
+            AsyncDynamixelControl::new(MockSerial::new(&[&status]), MockDelay {}, 1_000_000);
+        assert_eq!(
+            block_on(dxl.read_4byte(1, ControlTable::PresentPosition)),
+            Ok(2048)
+        );
+        let (uart, _) = dxl.release();
+        assert_eq!(
+            uart.rx_buf[..],
+            [0xFF, 0xFF, 0xFD, 0x00, 0x01, 0x07, 0x00, 0x02, 0x84, 0x00, 0x04, 0x00, 0x1D, 0x15]
+        );
+
+        let mut dxl = AsyncDynamixelControl::new(MockSerial::new(&[]), MockDelay {}, 1_000_000);
+        assert_eq!(
+            block_on(dxl.read_4byte(1, ControlTable::PresentPosition)),
+            Err(CommunicationResult::RxTimeout)
+        );
+        let mut dxl =
+            AsyncDynamixelControl::new(MockSerial::new(&[&status[..10]]), MockDelay {}, 1_000_000);
+        assert_eq!(
+            block_on(dxl.read_4byte(1, ControlTable::PresentPosition)),
+            Err(CommunicationResult::RxCorrupt)
+        );
+
+        // The status packet arriving after the previous timeout is dropped before sending.
+        let mut uart = MockSerial::new(&[&status]);
+        uart.push_stale(&[
+            0xFF, 0xFF, 0xFD, 0x00, 0x01, 0x08, 0x00, 0x55, 0x00, 0x00, 0x04, 0x00, 0x00, 0xEC,
+            0x38,
+        ]);
+        let mut dxl = AsyncDynamixelControl::new(uart, MockDelay {}, 1_000_000);
+        assert_eq!(
+            block_on(dxl.read_4byte(1, ControlTable::PresentPosition)),
+            Ok(2048)
+        );
+    }
+
+    #[test]
+    fn write() {
+        let status = [
+            0xFF, 0xFF, 0xFD, 0x00, 0x01, 0x04, 0x00, 0x55, 0x00, 0xA1, 0x0C,
+        ];
+        let mut dxl =
+            AsyncDynamixelControl::new(MockSerial::new(&[&status]), MockDelay {}, 1_000_000);
+        assert_eq!(
+            block_on(dxl.write_4byte(1, ControlTable::GoalPosition, 2048)),
+            Ok(())
+        );
+        assert_eq!(
+            block_on(dxl.write_4byte(1, ControlTable::PresentPosition, 2048)),
+            Err(CommunicationResult::ReadOnly)
+        );
+        let (uart, _) = dxl.release();
+        assert_eq!(
+            uart.rx_buf[..],
+            [
+                0xFF, 0xFF, 0xFD, 0x00, 0x01, 0x09, 0x00, 0x03, 0x74, 0x00, 0x00, 0x08, 0x00, 0x00,
+                0x42, 0x89
+            ]
+        );
+
+        // Torque Enable written by this driver is 1
+        let mut dxl =
+            AsyncDynamixelControl::new(MockSerial::new(&[&status]), MockDelay {}, 1_000_000);
+        assert_eq!(
+            block_on(dxl.write_1byte(1, ControlTable::TorqueEnable, 1)),
+            Ok(())
+        );
+        assert_eq!(
+            block_on(dxl.write_1byte(1, ControlTable::OperatingMode, 3)),
+            Err(CommunicationResult::TorqueEnabled)
+        );
+    }
+
+    #[test]
+    fn range_check() {
+        let mut dxl = AsyncDynamixelControl::new(MockSerial::new(&[]), MockDelay {}, 1_000_000);
+        dxl.set_range_check(true);
+        assert_eq!(
+            block_on(dxl.write_1byte(1, ControlTable::ReturnDelayTime, 255)),
+            Err(CommunicationResult::OutOfRange)
+        );
+        let (uart, _) = dxl.release();
+        assert!(uart.rx_buf.is_empty());
+    }
+
+    #[test]
+    fn echo_suppression() {
+        // TX and RX are tied together, so the ping comes back before the status packet.
+        let status = [
+            0xFF, 0xFF, 0xFD, 0x00, 0x01, 0x03, 0x00, 0x01, 0x19, 0x4E, 0xFF, 0xFF, 0xFD, 0x00,
+            0x01, 0x07, 0x00, 0x55, 0x00, 0x06, 0x04, 0x26, 0x65, 0x5D,
+        ];
+        let mut dxl =
+            AsyncDynamixelControl::new(MockSerial::new(&[&status]), MockDelay {}, 1_000_000);
+        dxl.set_echo_suppression(true);
+        assert_eq!(block_on(dxl.ping(1)), Ok((0x0406, 0x26)));
+
+        let mut collision = status;
+        collision[4] = 0x02;
+        let mut dxl =
+            AsyncDynamixelControl::new(MockSerial::new(&[&collision]), MockDelay {}, 1_000_000);
+        dxl.set_echo_suppression(true);
+        assert_eq!(
+            block_on(dxl.ping(1)),
+            Err(CommunicationResult::BusCollision)
+        );
+    }
+
+    #[test]
+    fn status_alert() {
+        // Present Temperature = 80(0x50) with Alert, then Hardware Error Status = Overheating and Overload
+        let status = [
+            0xFF, 0xFF, 0xFD, 0x00, 0x01, 0x05, 0x00, 0x55, 0x80, 0x50, 0xBA, 0xA0, 0xFF, 0xFF,
+            0xFD, 0x00, 0x01, 0x05, 0x00, 0x55, 0x80, 0x24, 0x82, 0xA1,
+        ];
+        let mut dxl = AsyncDynamixelControl::new(
+            MockSerial::new(&[&status[..12], &status[12..]]),
+            MockDelay {},
+            1_000_000,
+        );
+        match block_on(dxl.read_1byte(1, ControlTable::PresentTemperature)) {
+            Err(CommunicationResult::HardwareError { id, status }) => {
+                assert_eq!(id, 1);
+                assert!(status.contains(HardwareError::Overheating));
+                assert!(status.contains(HardwareError::Overload));
+            }
+            _ => panic!(),
+        }
+        let (uart, _) = dxl.release();
+        assert_eq!(
+            uart.rx_buf[14..],
+            [0xFF, 0xFF, 0xFD, 0x00, 0x01, 0x07, 0x00, 0x02, 0x46, 0x00, 0x01, 0x00, 0x3C, 0xA3]
+        );
+    }
+
+    #[test]
+    fn protocol1_read() {
+        // Present Position of Protocol 1.0 is 2 bytes
+        let status = [0xFF, 0xFF, 0x01, 0x04, 0x00, 0x00, 0x08, 0xF2];
+        let mut dxl =
+            AsyncDynamixelControl::new(MockSerial::new(&[&status]), MockDelay {}, 1_000_000);
+        dxl.set_protocol_version(ProtocolVersion::V1);
+        assert_eq!(
+            block_on(dxl.read_4byte(1, ControlTable::PresentPosition)),
+            Ok(2048)
+        );
+        let (uart, _) = dxl.release();
+        assert_eq!(
+            uart.rx_buf[..],
+            [0xFF, 0xFF, 0x01, 0x04, 0x02, 0x24, 0x02, 0xD2]
+        );
+    }
+
+    #[test]
+    fn sync_read() {
+        let status = [
+            0xFF, 0xFF, 0xFD, 0x00, 0x01, 0x08, 0x00, 0x55, 0x00, 0x00, 0x08, 0x00, 0x00, 0x1C,
+            0x38, 0xFF, 0xFF, 0xFD, 0x00, 0x02, 0x08, 0x00, 0x55, 0x00, 0x00, 0x04, 0x00, 0x00,
+            0x4C, 0x32,
+        ];
+        let mut dxl =
+            AsyncDynamixelControl::new(MockSerial::new(&[&status]), MockDelay {}, 1_000_000);
+        let result = block_on(dxl.sync_read(&[1, 2], ControlTable::PresentPosition, 4)).unwrap();
+        assert_eq!(result[0].0, 1);
+        assert_eq!(result[0].1.as_ref().unwrap()[..], [0x00, 0x08, 0x00, 0x00]);
+        assert_eq!(result[1].0, 2);
+        assert_eq!(result[1].1.as_ref().unwrap()[..], [0x00, 0x04, 0x00, 0x00]);
+        let (uart, _) = dxl.release();
+        assert_eq!(
+            uart.rx_buf[..],
+            [
+                0xFF, 0xFF, 0xFD, 0x00, 0xFE, 0x09, 0x00, 0x82, 0x84, 0x00, 0x04, 0x00, 0x01, 0x02,
+                0xCE, 0xFA
+            ]
+        );
+
+        // ID2 does not answer
+        let mut dxl =
+            AsyncDynamixelControl::new(MockSerial::new(&[&status[..15]]), MockDelay {}, 1_000_000);
+        let result = block_on(dxl.sync_read(&[1, 2], ControlTable::PresentPosition, 4)).unwrap();
+        assert!(result[0].1.is_ok());
+        assert_eq!(result[1].1, Err(CommunicationResult::RxTimeout));
+    }
+
+    #[test]
+    fn fast_sync_read() {
+        let status = [
+            0xFF, 0xFF, 0xFD, 0x00, 0xFE, 0x11, 0x00, 0x55, 0x00, 0x01, 0xA6, 0x00, 0x00, 0x00,
+            0x74, 0x3B, 0x00, 0x02, 0x1F, 0x08, 0x00, 0x00, 0x1C, 0x36,
+        ];
+        let mut dxl =
+            AsyncDynamixelControl::new(MockSerial::new(&[&status]), MockDelay {}, 1_000_000);
+        let result =
+            block_on(dxl.fast_sync_read(&[1, 2], ControlTable::PresentPosition, 4)).unwrap();
+        assert_eq!(result[0].0, 1);
+        assert_eq!(result[0].1.as_ref().unwrap()[..], [0xA6, 0x00, 0x00, 0x00]);
+        assert_eq!(result[1].0, 2);
+        assert_eq!(result[1].1.as_ref().unwrap()[..], [0x1F, 0x08, 0x00, 0x00]);
+        let (uart, _) = dxl.release();
+        assert_eq!(
+            uart.rx_buf[..],
+            [
+                0xFF, 0xFF, 0xFD, 0x00, 0xFE, 0x09, 0x00, 0x8A, 0x84, 0x00, 0x04, 0x00, 0x01, 0x02,
+                0x4D, 0x72
+            ]
+        );
+
+        // ID3 is not in the status packet.
+        let mut dxl =
+            AsyncDynamixelControl::new(MockSerial::new(&[&status]), MockDelay {}, 1_000_000);
+        let result =
+            block_on(dxl.fast_sync_read(&[1, 3], ControlTable::PresentPosition, 4)).unwrap();
+        assert!(result[0].1.is_ok());
+        assert_eq!(result[1].1, Err(CommunicationResult::RxCorrupt));
+    }
+
+    #[test]
+    fn fast_bulk_read() {
+        let status = [
+            0xFF, 0xFF, 0xFD, 0x00, 0xFE, 0x0F, 0x00, 0x55, 0x00, 0x01, 0xA6, 0x00, 0x00, 0x00,
+            0x34, 0x7B, 0x00, 0x02, 0x78, 0x03, 0xC7, 0x24,
+        ];
+        let mut dxl =
+            AsyncDynamixelControl::new(MockSerial::new(&[&status]), MockDelay {}, 1_000_000);
+        let result = block_on(dxl.fast_bulk_read(&[
+            (1, ControlTable::PresentPosition, 4),
+            (2, ControlTable::CurrentLimit, 2),
+        ]))
+        .unwrap();
+        assert_eq!(result[0].1.as_ref().unwrap()[..], [0xA6, 0x00, 0x00, 0x00]);
+        assert_eq!(result[1].1.as_ref().unwrap()[..], [0x78, 0x03]);
+        let (uart, _) = dxl.release();
+        assert_eq!(
+            uart.rx_buf[..],
+            [
+                0xFF, 0xFF, 0xFD, 0x00, 0xFE, 0x0D, 0x00, 0x9A, 0x01, 0x84, 0x00, 0x04, 0x00, 0x02,
+                0x26, 0x00, 0x02, 0x00, 0xCF, 0xCC
+            ]
+        );
+    }
+
+    #[test]
+    fn bulk_write() {
+        let mut dxl = AsyncDynamixelControl::new(MockSerial::new(&[]), MockDelay {}, 1_000_000);
+        let goal = 2048u32.to_le_bytes();
+        let params: [(u8, ControlTable, &[u8]); 2] = [
+            (1, ControlTable::TorqueEnable, &[1]),
+            (2, ControlTable::GoalPosition, &goal),
+        ];
+        assert_eq!(block_on(dxl.bulk_write(&params)), Ok(()));
+        // Goal Position is 4 bytes
+        let params: [(u8, ControlTable, &[u8]); 1] = [(2, ControlTable::GoalPosition, &goal[..2])];
+        assert_eq!(
+            block_on(dxl.bulk_write(&params)),
+            Err(CommunicationResult::NotAvailable)
+        );
+        let (uart, _) = dxl.release();
+        assert_eq!(
+            uart.rx_buf[..],
+            [
+                0xFF, 0xFF, 0xFD, 0x00, 0xFE, 0x12, 0x00, 0x93, 0x01, 0x40, 0x00, 0x01, 0x00, 0x01,
+                0x02, 0x74, 0x00, 0x04, 0x00, 0x00, 0x08, 0x00, 0x00, 0xE9, 0x84
+            ]
+        );
+    }
+}
@@ -1,6 +1,5 @@
 #[cfg(any(feature = "xm430", feature = "xc330",))]
 #[allow(dead_code)]
-
 pub enum OperatingMode {
     CurrentControlMode,
     VelocityControlMode,
@@ -24,6 +23,137 @@ impl OperatingMode {
     }
 }
 
+#[allow(dead_code)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FactoryResetMode {
+    ResetAll,
+    ResetAllExceptID,
+    ResetAllExceptIDAndBaudrate,
+}
+
+#[allow(dead_code)]
+impl FactoryResetMode {
+    pub fn to_value(&self) -> u8 {
+        match self {
+            FactoryResetMode::ResetAll => 0xFF,
+            FactoryResetMode::ResetAllExceptID => 0x01,
+            FactoryResetMode::ResetAllExceptIDAndBaudrate => 0x02,
+        }
+    }
+}
+
+#[allow(dead_code)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HardwareError {
+    InputVoltage,
+    Overheating,
+    MotorEncoder,
+    ElectricalShock,
+    Overload,
+}
+
+#[allow(dead_code)]
+impl HardwareError {
+    pub fn to_bit(&self) -> u8 {
+        match self {
+            HardwareError::InputVoltage => 0x01,
+            HardwareError::Overheating => 0x04,
+            HardwareError::MotorEncoder => 0x08,
+            HardwareError::ElectricalShock => 0x10,
+            HardwareError::Overload => 0x20,
+        }
+    }
+}
+
+/// Set of HardwareError read from Hardware Error Status.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub struct HardwareErrorStatus(u8);
+
+#[allow(dead_code)]
+impl HardwareErrorStatus {
+    pub fn from_value(value: u8) -> Self {
+        Self(value)
+    }
+    pub fn to_value(&self) -> u8 {
+        self.0
+    }
+    pub fn contains(&self, error: HardwareError) -> bool {
+        self.0 & error.to_bit() != 0
+    }
+    pub fn insert(&mut self, error: HardwareError) {
+        self.0 |= error.to_bit();
+    }
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+}
+
+/// Bits of Drive Mode.
+/// Built from the value read from the device, e.g. DriveMode::from_value(v).reverse(true).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub struct DriveMode(u8);
+
+impl DriveMode {
+    const REVERSE: u8 = 0x01;
+    const SLAVE: u8 = 0x02;
+    const TIME_BASED_PROFILE: u8 = 0x04;
+    const TORQUE_ON_BY_GOAL_UPDATE: u8 = 0x08;
+
+    pub fn from_value(value: u8) -> Self {
+        Self(value)
+    }
+    pub fn to_value(&self) -> u8 {
+        self.0
+    }
+    /// Reverse the direction of rotation.
+    pub fn reverse(self, enable: bool) -> Self {
+        self.with(Self::REVERSE, enable)
+    }
+    /// Slave of the dual joint (2XL430 and 2XC430). Master if false.
+    pub fn slave(self, enable: bool) -> Self {
+        self.with(Self::SLAVE, enable)
+    }
+    /// Time-based Profile. Velocity-based Profile if false.
+    pub fn time_based_profile(self, enable: bool) -> Self {
+        self.with(Self::TIME_BASED_PROFILE, enable)
+    }
+    /// Torque is turned on by updating goal items (XL330 and XC330).
+    pub fn torque_on_by_goal_update(self, enable: bool) -> Self {
+        self.with(Self::TORQUE_ON_BY_GOAL_UPDATE, enable)
+    }
+    pub fn is_reverse(&self) -> bool {
+        self.0 & Self::REVERSE != 0
+    }
+    pub fn is_slave(&self) -> bool {
+        self.0 & Self::SLAVE != 0
+    }
+    pub fn is_time_based_profile(&self) -> bool {
+        self.0 & Self::TIME_BASED_PROFILE != 0
+    }
+    pub fn is_torque_on_by_goal_update(&self) -> bool {
+        self.0 & Self::TORQUE_ON_BY_GOAL_UPDATE != 0
+    }
+    fn with(self, bit: u8, enable: bool) -> Self {
+        if enable {
+            Self(self.0 | bit)
+        } else {
+            Self(self.0 & !bit)
+        }
+    }
+}
+
+/// Limit registers read from the device.
+/// None if the device does not have the item.
+/// Position limits are used only in Position Control Mode, so they are None in the other modes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub struct DeviceLimits {
+    pub max_position: Option<i32>,
+    pub min_position: Option<i32>,
+    pub current: Option<i32>,
+    pub velocity: Option<i32>,
+    pub pwm: Option<i32>,
+}
+
 pub trait Pulse2Deg<T> {
     fn pulse2deg(self) -> T;
     fn deg2pulse(self) -> T;
@@ -1,9 +1,234 @@
+#[allow(dead_code)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum DynamixelModel{
-    Xm430W350,
+    Xl330M077,
+    Xl330M288,
+    Xc330M181,
+    Xc330M288,
     Xc330T181,
+    Xc330T288,
+    Xl430W250,
+    TwoXl430W250,
+    Xc430W150,
+    Xc430W240,
+    TwoXc430W250,
+    Xm430W210,
+    Xm430W350,
+    Xh430W210,
+    Xh430W350,
+    Xh430V210,
+    Xh430V350,
+    Xm540W150,
+    Xm540W270,
+    Xh540W150,
+    Xh540W270,
+    Xh540V150,
+    Xh540V270,
+    // P series (PRO+ H54P, H42P, M54P and M42P are the same actuators)
+    Ph54_200S500,
+    Ph54_100S500,
+    Ph42_020S300,
+    Pm54_060S250,
+    Pm54_040S250,
+    Pm42_010S260,
+    // AX series (Protocol 1.0 only)
+    Ax12A,
+    Ax18A,
+    Ax12W,
+}
+
+impl Default for DynamixelModel {
+    /// Used for the devices whose model is not registered.
+    fn default() -> Self {
+        DynamixelModel::Xc330T181
+    }
+}
+
+#[allow(dead_code)]
+impl DynamixelModel {
+    /// Model Number answered by ping.
+    pub fn from_model_number(model_number: u16) -> Option<Self> {
+        match model_number {
+            1190 => Some(DynamixelModel::Xl330M077),
+            1200 => Some(DynamixelModel::Xl330M288),
+            1230 => Some(DynamixelModel::Xc330M181),
+            1240 => Some(DynamixelModel::Xc330M288),
+            1210 => Some(DynamixelModel::Xc330T181),
+            1220 => Some(DynamixelModel::Xc330T288),
+            1060 => Some(DynamixelModel::Xl430W250),
+            1090 => Some(DynamixelModel::TwoXl430W250),
+            1070 => Some(DynamixelModel::Xc430W150),
+            1080 => Some(DynamixelModel::Xc430W240),
+            1160 => Some(DynamixelModel::TwoXc430W250),
+            1030 => Some(DynamixelModel::Xm430W210),
+            1020 => Some(DynamixelModel::Xm430W350),
+            1010 => Some(DynamixelModel::Xh430W210),
+            1000 => Some(DynamixelModel::Xh430W350),
+            1050 => Some(DynamixelModel::Xh430V210),
+            1040 => Some(DynamixelModel::Xh430V350),
+            1130 => Some(DynamixelModel::Xm540W150),
+            1120 => Some(DynamixelModel::Xm540W270),
+            1110 => Some(DynamixelModel::Xh540W150),
+            1100 => Some(DynamixelModel::Xh540W270),
+            1150 => Some(DynamixelModel::Xh540V150),
+            1140 => Some(DynamixelModel::Xh540V270),
+            2020 => Some(DynamixelModel::Ph54_200S500),
+            2010 => Some(DynamixelModel::Ph54_100S500),
+            2000 => Some(DynamixelModel::Ph42_020S300),
+            2120 => Some(DynamixelModel::Pm54_060S250),
+            2110 => Some(DynamixelModel::Pm54_040S250),
+            2100 => Some(DynamixelModel::Pm42_010S260),
+            12 => Some(DynamixelModel::Ax12A),
+            18 => Some(DynamixelModel::Ax18A),
+            300 => Some(DynamixelModel::Ax12W),
+            _ => None,
+        }
+    }
+    pub fn to_model_number(&self) -> u16 {
+        match self {
+            DynamixelModel::Xl330M077 => 1190,
+            DynamixelModel::Xl330M288 => 1200,
+            DynamixelModel::Xc330M181 => 1230,
+            DynamixelModel::Xc330M288 => 1240,
+            DynamixelModel::Xc330T181 => 1210,
+            DynamixelModel::Xc330T288 => 1220,
+            DynamixelModel::Xl430W250 => 1060,
+            DynamixelModel::TwoXl430W250 => 1090,
+            DynamixelModel::Xc430W150 => 1070,
+            DynamixelModel::Xc430W240 => 1080,
+            DynamixelModel::TwoXc430W250 => 1160,
+            DynamixelModel::Xm430W210 => 1030,
+            DynamixelModel::Xm430W350 => 1020,
+            DynamixelModel::Xh430W210 => 1010,
+            DynamixelModel::Xh430W350 => 1000,
+            DynamixelModel::Xh430V210 => 1050,
+            DynamixelModel::Xh430V350 => 1040,
+            DynamixelModel::Xm540W150 => 1130,
+            DynamixelModel::Xm540W270 => 1120,
+            DynamixelModel::Xh540W150 => 1110,
+            DynamixelModel::Xh540W270 => 1100,
+            DynamixelModel::Xh540V150 => 1150,
+            DynamixelModel::Xh540V270 => 1140,
+            DynamixelModel::Ph54_200S500 => 2020,
+            DynamixelModel::Ph54_100S500 => 2010,
+            DynamixelModel::Ph42_020S300 => 2000,
+            DynamixelModel::Pm54_060S250 => 2120,
+            DynamixelModel::Pm54_040S250 => 2110,
+            DynamixelModel::Pm42_010S260 => 2100,
+            DynamixelModel::Ax12A => 12,
+            DynamixelModel::Ax18A => 18,
+            DynamixelModel::Ax12W => 300,
+        }
+    }
+    /// Unit of current registers [mA].
+    /// XL430 and XC430 have no current sensor.
+    pub fn current_unit(&self) -> f32 {
+        match self {
+            DynamixelModel::Xm430W210
+            | DynamixelModel::Xm430W350
+            | DynamixelModel::Xm540W150
+            | DynamixelModel::Xm540W270
+            | DynamixelModel::Xh540W150
+            | DynamixelModel::Xh540W270
+            | DynamixelModel::Xh540V150
+            | DynamixelModel::Xh540V270 => 2.69,
+            DynamixelModel::Xh430W210
+            | DynamixelModel::Xh430W350
+            | DynamixelModel::Xh430V210
+            | DynamixelModel::Xh430V350 => 1.34,
+            _ => 1.0,
+        }
+    }
+    /// Unit of PWM registers [%].
+    pub fn pwm_unit(&self) -> f32 {
+        if self.is_p_series() {
+            0.0498
+        } else {
+            0.113
+        }
+    }
+    /// Unit of velocity registers [rev/min].
+    pub fn velocity_unit(&self) -> f32 {
+        if self.is_p_series() {
+            0.01
+        } else {
+            0.229
+        }
+    }
+    /// Unit of acceleration registers [rev/min^2].
+    pub fn acceleration_unit(&self) -> f32 {
+        if self.is_p_series() {
+            1.0
+        } else {
+            214.577
+        }
+    }
+    /// Position pulse per revolution.
+    pub fn position_resolution(&self) -> u32 {
+        match self {
+            DynamixelModel::Ph54_200S500 | DynamixelModel::Ph54_100S500 => 1_003_846,
+            DynamixelModel::Ph42_020S300 => 607_500,
+            DynamixelModel::Pm54_060S250 | DynamixelModel::Pm54_040S250 => 502_834,
+            DynamixelModel::Pm42_010S260 => 526_374,
+            // 1024 pulse per 300 deg
+            DynamixelModel::Ax12A | DynamixelModel::Ax18A | DynamixelModel::Ax12W => 1229,
+            _ => 4096,
+        }
+    }
+    /// Position pulse at 0 [rad].
+    pub fn home_position(&self) -> i32 {
+        if self.is_p_series() {
+            0
+        } else if self.is_ax_series() {
+            512
+        } else {
+            2048
+        }
+    }
+    /// P series has the RAM area at 512 and after.
+    pub fn is_p_series(&self) -> bool {
+        matches!(
+            self,
+            DynamixelModel::Ph54_200S500
+                | DynamixelModel::Ph54_100S500
+                | DynamixelModel::Ph42_020S300
+                | DynamixelModel::Pm54_060S250
+                | DynamixelModel::Pm54_040S250
+                | DynamixelModel::Pm42_010S260
+        )
+    }
+    /// AX series lacks the PID gains and the items after Punch in Protocol 1.0 control table.
+    pub fn is_ax_series(&self) -> bool {
+        matches!(
+            self,
+            DynamixelModel::Ax12A | DynamixelModel::Ax18A | DynamixelModel::Ax12W
+        )
+    }
+    pub fn has_current_sensor(&self) -> bool {
+        !matches!(
+            self,
+            DynamixelModel::Xl430W250
+                | DynamixelModel::TwoXl430W250
+                | DynamixelModel::Xc430W150
+                | DynamixelModel::Xc430W240
+                | DynamixelModel::TwoXc430W250
+        )
+    }
+    fn is_330_series(&self) -> bool {
+        matches!(
+            self,
+            DynamixelModel::Xl330M077
+                | DynamixelModel::Xl330M288
+                | DynamixelModel::Xc330M181
+                | DynamixelModel::Xc330M288
+                | DynamixelModel::Xc330T181
+                | DynamixelModel::Xc330T288
+        )
+    }
 }
 
 #[allow(dead_code)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum ControlTable {
     ModelNumber,
     ModelInformation,
@@ -22,6 +247,7 @@ pub enum ControlTable {
     MinVoltageLimit,
     PWMLimit,
     CurrentLimit,
+    AccelerationLimit,
     VelocityLimit,
     MaxPositionLimit,
     MinPositionLimit,
@@ -101,9 +327,40 @@ pub enum ControlTable {
     IndirectData20,
 }
 
+/// Memory area of a control table item.
+/// EEPROM items can be written only while Torque Enable is off.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MemoryArea {
+    EEPROM,
+    RAM,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Access {
+    ReadOnly,
+    ReadWrite,
+}
+
+/// Everything known about a control table item on a model.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RegisterDescriptor {
+    pub address: u16,
+    pub size: u16,
+    pub area: MemoryArea,
+    pub access: Access,
+    /// Valid range of the raw value. None for read only items.
+    pub range: Option<(i32, i32)>,
+    pub unit: f32,
+}
+
 #[allow(dead_code)]
 impl ControlTable {
-    pub fn to_address(&self) -> u16 {
+    pub fn to_address(&self, model: &DynamixelModel) -> u16 {
+        if model.is_p_series() {
+            if let Some(v) = self.p_series_address() {
+                return v;
+            }
+        }
         match self {
             ControlTable::ModelNumber => 0,
             ControlTable::ModelInformation => 2,
@@ -122,6 +379,7 @@ impl ControlTable {
             ControlTable::MinVoltageLimit => 34,
             ControlTable::PWMLimit => 36,
             ControlTable::CurrentLimit => 38,
+            ControlTable::AccelerationLimit => 40,
             ControlTable::VelocityLimit => 44,
             ControlTable::MaxPositionLimit => 48,
             ControlTable::MinPositionLimit => 52,
@@ -202,6 +460,85 @@ impl ControlTable {
         }
     }
 
+    /// P series shares the EEPROM addresses of X series up to Min Position Limit.
+    /// Indirect Address and the RAM area are at different addresses.
+    fn p_series_address(&self) -> Option<u16> {
+        match self {
+            ControlTable::TorqueEnable => Some(512),
+            ControlTable::LED => Some(513), // LED Red
+            ControlTable::StatusReturnLevel => Some(516),
+            ControlTable::RegisteredInstruction => Some(517),
+            ControlTable::HardwareErrorStatus => Some(518),
+            ControlTable::VelocityIGain => Some(524),
+            ControlTable::VelocityPgain => Some(526),
+            ControlTable::PositionDGain => Some(528),
+            ControlTable::PositionIGain => Some(530),
+            ControlTable::PositionPGain => Some(532),
+            ControlTable::Feedforward2ndGain => Some(536),
+            ControlTable::Feedforward1stGain => Some(538),
+            ControlTable::BusWatchdog => Some(546),
+            ControlTable::GoalPWM => Some(548),
+            ControlTable::GoalCurrent => Some(550),
+            ControlTable::GoalVelocity => Some(552),
+            ControlTable::ProfileAccleration => Some(556),
+            ControlTable::ProfileVelocity => Some(560),
+            ControlTable::GoalPosition => Some(564),
+            ControlTable::RealtimeTick => Some(568),
+            ControlTable::Moving => Some(570),
+            ControlTable::MovingStatus => Some(571),
+            ControlTable::PresentPWM => Some(572),
+            ControlTable::PresentCurrent => Some(574),
+            ControlTable::PresentVelocity => Some(576),
+            ControlTable::PresentPosition => Some(580),
+            ControlTable::VelocityTrajectory => Some(584),
+            ControlTable::PositionTrajectory => Some(588),
+            ControlTable::PresentInputVoltage => Some(592),
+            ControlTable::PresentTemperature => Some(594),
+            ControlTable::BackupReady => Some(878),
+            ControlTable::IndirectAddress1 => Some(168),
+            ControlTable::IndirectAddress2 => Some(170),
+            ControlTable::IndirectAddress3 => Some(172),
+            ControlTable::IndirectAddress4 => Some(174),
+            ControlTable::IndirectAddress5 => Some(176),
+            ControlTable::IndirectAddress6 => Some(178),
+            ControlTable::IndirectAddress7 => Some(180),
+            ControlTable::IndirectAddress8 => Some(182),
+            ControlTable::IndirectAddress9 => Some(184),
+            ControlTable::IndirectAddress10 => Some(186),
+            ControlTable::IndirectAddress11 => Some(188),
+            ControlTable::IndirectAddress12 => Some(190),
+            ControlTable::IndirectAddress13 => Some(192),
+            ControlTable::IndirectAddress14 => Some(194),
+            ControlTable::IndirectAddress15 => Some(196),
+            ControlTable::IndirectAddress16 => Some(198),
+            ControlTable::IndirectAddress17 => Some(200),
+            ControlTable::IndirectAddress18 => Some(202),
+            ControlTable::IndirectAddress19 => Some(204),
+            ControlTable::IndirectAddress20 => Some(206),
+            ControlTable::IndirectData1 => Some(634),
+            ControlTable::IndirectData2 => Some(635),
+            ControlTable::IndirectData3 => Some(636),
+            ControlTable::IndirectData4 => Some(637),
+            ControlTable::IndirectData5 => Some(638),
+            ControlTable::IndirectData6 => Some(639),
+            ControlTable::IndirectData7 => Some(640),
+            ControlTable::IndirectData8 => Some(641),
+            ControlTable::IndirectData9 => Some(642),
+            ControlTable::IndirectData10 => Some(643),
+            ControlTable::IndirectData11 => Some(644),
+            ControlTable::IndirectData12 => Some(645),
+            ControlTable::IndirectData13 => Some(646),
+            ControlTable::IndirectData14 => Some(647),
+            ControlTable::IndirectData15 => Some(648),
+            ControlTable::IndirectData16 => Some(649),
+            ControlTable::IndirectData17 => Some(650),
+            ControlTable::IndirectData18 => Some(651),
+            ControlTable::IndirectData19 => Some(652),
+            ControlTable::IndirectData20 => Some(653),
+            _ => None,
+        }
+    }
+
     pub fn to_size(&self) -> u16 {
         match self {
             ControlTable::ModelNumber => 2,
@@ -221,6 +558,7 @@ impl ControlTable {
             ControlTable::MinVoltageLimit => 2,
             ControlTable::PWMLimit => 2,
             ControlTable::CurrentLimit => 2,
+            ControlTable::AccelerationLimit => 4,
             ControlTable::VelocityLimit => 4,
             ControlTable::MaxPositionLimit => 4,
             ControlTable::MinPositionLimit => 4,
@@ -301,6 +639,252 @@ impl ControlTable {
         }
     }
 
+    /// Address in the Protocol 1.0 control table of MX series.
+    /// AX series has Compliance Margin and Slope at 26-28 instead of the gains.
+    pub fn to_protocol1_address(&self, model: &DynamixelModel) -> Option<u8> {
+        if model.is_ax_series() {
+            match self {
+                ControlTable::HomingOffset
+                | ControlTable::PositionDGain
+                | ControlTable::PositionIGain
+                | ControlTable::PositionPGain
+                | ControlTable::PresentCurrent
+                | ControlTable::GoalCurrent
+                | ControlTable::ProfileAccleration => return None,
+                _ => {}
+            }
+        }
+        match self {
+            ControlTable::ModelNumber => Some(0),
+            ControlTable::FirmwareVersion => Some(2),
+            ControlTable::ID => Some(3),
+            ControlTable::BaudRate => Some(4),
+            ControlTable::ReturnDelayTime => Some(5),
+            ControlTable::MinPositionLimit => Some(6), // CW Angle Limit
+            ControlTable::MaxPositionLimit => Some(8), // CCW Angle Limit
+            ControlTable::TemperatureLimit => Some(11),
+            ControlTable::MinVoltageLimit => Some(12),
+            ControlTable::MaxVoltageLimit => Some(13),
+            ControlTable::StatusReturnLevel => Some(16),
+            ControlTable::Shutdown => Some(18),
+            ControlTable::HomingOffset => Some(20), // Multi Turn Offset
+            ControlTable::TorqueEnable => Some(24),
+            ControlTable::LED => Some(25),
+            ControlTable::PositionDGain => Some(26),
+            ControlTable::PositionIGain => Some(27),
+            ControlTable::PositionPGain => Some(28),
+            ControlTable::GoalPosition => Some(30),
+            ControlTable::GoalVelocity => Some(32), // Moving Speed
+            ControlTable::PresentPosition => Some(36),
+            ControlTable::PresentVelocity => Some(38), // Present Speed
+            ControlTable::PresentInputVoltage => Some(42),
+            ControlTable::PresentTemperature => Some(43),
+            ControlTable::RegisteredInstruction => Some(44),
+            ControlTable::Moving => Some(46),
+            ControlTable::PresentCurrent => Some(68),
+            ControlTable::GoalCurrent => Some(71), // Goal Torque
+            ControlTable::ProfileAccleration => Some(73), // Goal Acceleration
+            _ => None,
+        }
+    }
+    pub fn to_protocol1_size(&self) -> Option<u16> {
+        match self {
+            ControlTable::ModelNumber => Some(2),
+            ControlTable::FirmwareVersion => Some(1),
+            ControlTable::ID => Some(1),
+            ControlTable::BaudRate => Some(1),
+            ControlTable::ReturnDelayTime => Some(1),
+            ControlTable::MinPositionLimit => Some(2),
+            ControlTable::MaxPositionLimit => Some(2),
+            ControlTable::TemperatureLimit => Some(1),
+            ControlTable::MinVoltageLimit => Some(1),
+            ControlTable::MaxVoltageLimit => Some(1),
+            ControlTable::StatusReturnLevel => Some(1),
+            ControlTable::Shutdown => Some(1),
+            ControlTable::HomingOffset => Some(2),
+            ControlTable::TorqueEnable => Some(1),
+            ControlTable::LED => Some(1),
+            ControlTable::PositionDGain => Some(1),
+            ControlTable::PositionIGain => Some(1),
+            ControlTable::PositionPGain => Some(1),
+            ControlTable::GoalPosition => Some(2),
+            ControlTable::GoalVelocity => Some(2),
+            ControlTable::PresentPosition => Some(2),
+            ControlTable::PresentVelocity => Some(2),
+            ControlTable::PresentInputVoltage => Some(1),
+            ControlTable::PresentTemperature => Some(1),
+            ControlTable::RegisteredInstruction => Some(1),
+            ControlTable::Moving => Some(1),
+            ControlTable::PresentCurrent => Some(2),
+            ControlTable::GoalCurrent => Some(2),
+            ControlTable::ProfileAccleration => Some(1),
+            _ => None,
+        }
+    }
+
+    /// X-series share the same address, but some items are missing on some models.
+    /// Present Current of XL430 and XC430 is Present Load [0.1%] instead.
+    pub fn is_supported(&self, model: &DynamixelModel) -> bool {
+        match self {
+            ControlTable::CurrentLimit
+            | ControlTable::GoalCurrent
+            | ControlTable::PresentCurrent => model.has_current_sensor(),
+            ControlTable::PWMSlope => model.is_330_series(),
+            ControlTable::StartupConfiguration => !model.is_p_series(),
+            ControlTable::AccelerationLimit => model.is_p_series(),
+            _ => true,
+        }
+    }
+
+    pub fn to_descriptor(&self, model: &DynamixelModel) -> RegisterDescriptor {
+        RegisterDescriptor {
+            address: self.to_address(model),
+            size: self.to_size(),
+            area: self.to_area(model),
+            access: self.to_access(),
+            range: self.to_range(model),
+            unit: self.to_unit(model),
+        }
+    }
+
+    /// EEPROM area is below 64 on X-series and below 512 on P-series.
+    pub fn to_area(&self, model: &DynamixelModel) -> MemoryArea {
+        let ram_start = if model.is_p_series() { 512 } else { 64 };
+        if self.to_address(model) < ram_start {
+            MemoryArea::EEPROM
+        } else {
+            MemoryArea::RAM
+        }
+    }
+
+    pub fn to_access(&self) -> Access {
+        match self {
+            ControlTable::ModelNumber
+            | ControlTable::ModelInformation
+            | ControlTable::FirmwareVersion
+            | ControlTable::RegisteredInstruction
+            | ControlTable::HardwareErrorStatus
+            | ControlTable::RealtimeTick
+            | ControlTable::Moving
+            | ControlTable::MovingStatus
+            | ControlTable::PresentPWM
+            | ControlTable::PresentCurrent
+            | ControlTable::PresentVelocity
+            | ControlTable::PresentPosition
+            | ControlTable::VelocityTrajectory
+            | ControlTable::PositionTrajectory
+            | ControlTable::PresentInputVoltage
+            | ControlTable::PresentTemperature
+            | ControlTable::BackupReady => Access::ReadOnly,
+            _ => Access::ReadWrite,
+        }
+    }
+
+    /// Range of the raw value accepted by the item.
+    /// Items whose range depends on other items (e.g. Goal Position in Position Control Mode) use the widest range.
+    pub fn to_range(&self, model: &DynamixelModel) -> Option<(i32, i32)> {
+        if self.to_access() == Access::ReadOnly {
+            return None;
+        }
+        let p = model.is_p_series();
+        let half_turn = model.position_resolution() as i32 / 2;
+        match self {
+            ControlTable::ID => Some((0, 252)),
+            ControlTable::BaudRate => Some((0, 7)),
+            ControlTable::ReturnDelayTime => Some((0, 254)),
+            ControlTable::DriveMode => Some((0, 15)),
+            ControlTable::OperatingMode => Some((0, 16)),
+            ControlTable::SecondaryID => Some((0, 255)),
+            ControlTable::ProtocolType => Some((1, 2)),
+            ControlTable::HomingOffset => Some((-1_044_479, 1_044_479)),
+            ControlTable::MovingThreshold => Some((0, 1023)),
+            ControlTable::TemperatureLimit => Some((0, 100)),
+            ControlTable::MaxVoltageLimit | ControlTable::MinVoltageLimit => match model {
+                _ if p => Some((150, 350)),
+                _ if model.is_330_series() => Some((31, 70)),
+                DynamixelModel::Xl430W250
+                | DynamixelModel::TwoXl430W250
+                | DynamixelModel::Xc430W150
+                | DynamixelModel::Xc430W240
+                | DynamixelModel::TwoXc430W250 => Some((60, 140)),
+                // 24V models
+                DynamixelModel::Xh430V210
+                | DynamixelModel::Xh430V350
+                | DynamixelModel::Xh540V150
+                | DynamixelModel::Xh540V270 => Some((110, 300)),
+                _ => Some((95, 160)),
+            },
+            ControlTable::PWMLimit => Some((0, if p { 2009 } else { 885 })),
+            ControlTable::CurrentLimit => Some((0, if p { 32767 } else { 2047 })),
+            ControlTable::AccelerationLimit => Some((0, 32767)),
+            ControlTable::VelocityLimit => Some((0, if p { 32767 } else { 2047 })),
+            ControlTable::MaxPositionLimit | ControlTable::MinPositionLimit => {
+                if p {
+                    Some((-half_turn, half_turn))
+                } else {
+                    Some((0, 4095))
+                }
+            }
+            ControlTable::StartupConfiguration => Some((0, 3)),
+            ControlTable::PWMSlope => Some((1, 255)),
+            ControlTable::Shutdown => Some((0, 255)),
+            ControlTable::TorqueEnable => Some((0, 1)),
+            ControlTable::LED => Some((0, if p { 255 } else { 1 })),
+            ControlTable::StatusReturnLevel => Some((0, 2)),
+            ControlTable::VelocityIGain
+            | ControlTable::VelocityPgain
+            | ControlTable::PositionDGain
+            | ControlTable::PositionIGain
+            | ControlTable::PositionPGain
+            | ControlTable::Feedforward2ndGain
+            | ControlTable::Feedforward1stGain => Some((0, if p { 32767 } else { 16383 })),
+            ControlTable::BusWatchdog => Some((0, 127)),
+            ControlTable::GoalPWM => {
+                let limit = if p { 2009 } else { 885 };
+                Some((-limit, limit))
+            }
+            ControlTable::GoalCurrent | ControlTable::GoalVelocity => {
+                let limit = if p { 32767 } else { 2047 };
+                Some((-limit, limit))
+            }
+            ControlTable::ProfileAccleration | ControlTable::ProfileVelocity => Some((0, 32767)),
+            ControlTable::GoalPosition => {
+                if p {
+                    Some((-half_turn, half_turn))
+                } else {
+                    Some((-1_048_575, 1_048_575))
+                }
+            }
+            ControlTable::IndirectAddress1
+            | ControlTable::IndirectAddress2
+            | ControlTable::IndirectAddress3
+            | ControlTable::IndirectAddress4
+            | ControlTable::IndirectAddress5
+            | ControlTable::IndirectAddress6
+            | ControlTable::IndirectAddress7
+            | ControlTable::IndirectAddress8
+            | ControlTable::IndirectAddress9
+            | ControlTable::IndirectAddress10
+            | ControlTable::IndirectAddress11
+            | ControlTable::IndirectAddress12
+            | ControlTable::IndirectAddress13
+            | ControlTable::IndirectAddress14
+            | ControlTable::IndirectAddress15
+            | ControlTable::IndirectAddress16
+            | ControlTable::IndirectAddress17
+            | ControlTable::IndirectAddress18
+            | ControlTable::IndirectAddress19
+            | ControlTable::IndirectAddress20 => {
+                if p {
+                    Some((512, 1023))
+                } else {
+                    Some((64, 661))
+                }
+            }
+            _ => Some((0, 255)), // Indirect Data
+        }
+    }
+
     pub fn to_unit(&self, model: &DynamixelModel) -> f32 {
         match self {
             ControlTable::ModelNumber => 1.0,
@@ -314,13 +898,14 @@ impl ControlTable {
             ControlTable::SecondaryID => 1.0,
             ControlTable::ProtocolType => 1.0,
             ControlTable::HomingOffset => 2.0,
-            ControlTable::MovingThreshold => 0.229,
+            ControlTable::MovingThreshold => model.velocity_unit(),
             ControlTable::TemperatureLimit => 1.0,
             ControlTable::MaxVoltageLimit => 0.1,
             ControlTable::MinVoltageLimit => 0.1,
-            ControlTable::PWMLimit => 0.113,
-            ControlTable::CurrentLimit => 1.0,
-            ControlTable::VelocityLimit => 0.229,
+            ControlTable::PWMLimit => model.pwm_unit(),
+            ControlTable::CurrentLimit => model.current_unit(),
+            ControlTable::AccelerationLimit => model.acceleration_unit(),
+            ControlTable::VelocityLimit => model.velocity_unit(),
             ControlTable::MaxPositionLimit => 1.0,
             ControlTable::MinPositionLimit => 1.0,
             ControlTable::StartupConfiguration => 1.0,
@@ -339,25 +924,20 @@ impl ControlTable {
             ControlTable::Feedforward2ndGain => 1.0,
             ControlTable::Feedforward1stGain => 1.0,
             ControlTable::BusWatchdog => 20.0,
-            ControlTable::GoalPWM => 0.113,
-            ControlTable::GoalCurrent => 1.0,
-            ControlTable::GoalVelocity => 0.229,
-            ControlTable::ProfileAccleration => 214.577,
-            ControlTable::ProfileVelocity => 0.229,
+            ControlTable::GoalPWM => model.pwm_unit(),
+            ControlTable::GoalCurrent => model.current_unit(),
+            ControlTable::GoalVelocity => model.velocity_unit(),
+            ControlTable::ProfileAccleration => model.acceleration_unit(),
+            ControlTable::ProfileVelocity => model.velocity_unit(),
             ControlTable::GoalPosition => 1.0,
             ControlTable::RealtimeTick => 1.0,
             ControlTable::Moving => 1.0,
             ControlTable::MovingStatus => 1.0,
-            ControlTable::PresentPWM => 0.113,
-            ControlTable::PresentCurrent => {
-                match model{
-                    DynamixelModel::Xm430W350 => 2.69,
-                    DynamixelModel::Xc330T181 => 1.0,
-                }
-            },
-            ControlTable::PresentVelocity => 0.229,
+            ControlTable::PresentPWM => model.pwm_unit(),
+            ControlTable::PresentCurrent => model.current_unit(),
+            ControlTable::PresentVelocity => model.velocity_unit(),
             ControlTable::PresentPosition => 1.0,
-            ControlTable::VelocityTrajectory => 0.229,
+            ControlTable::VelocityTrajectory => model.velocity_unit(),
             ControlTable::PositionTrajectory => 1.0,
             ControlTable::PresentInputVoltage => 0.1,
             ControlTable::PresentTemperature => 1.0,
@@ -407,15 +987,34 @@ impl ControlTable {
 }
 
 #[cfg(test)]
+#[allow(clippy::bool_assert_comparison)]
 mod tests {
+    use crate::control_table::Access;
     use crate::control_table::ControlTable;
     use crate::control_table::DynamixelModel;
+    use crate::control_table::MemoryArea;
     
     #[test]
     fn to_address_xc330() {
         let name = ControlTable::ModelNumber;
-        assert_eq!(name.to_address(), 0);
-        assert_eq!(ControlTable::TorqueEnable.to_address(), 64)
+        let model = DynamixelModel::Xc330T181;
+        assert_eq!(name.to_address(&model), 0);
+        assert_eq!(ControlTable::TorqueEnable.to_address(&model), 64)
+    }
+    #[test]
+    fn to_address_p_series() {
+        let model = DynamixelModel::Ph54_200S500;
+        assert_eq!(ControlTable::ModelNumber.to_address(&model), 0);
+        assert_eq!(ControlTable::AccelerationLimit.to_address(&model), 40);
+        assert_eq!(ControlTable::Shutdown.to_address(&model), 63);
+        assert_eq!(
+            ControlTable::Shutdown.to_address(&DynamixelModel::Xm430W210),
+            63
+        );
+        assert_eq!(ControlTable::GoalPosition.to_address(&model), 564);
+        assert_eq!(ControlTable::IndirectAddress2.to_address(&model), 170);
+        assert_eq!(ControlTable::IndirectData20.to_address(&model), 653);
+        assert_eq!(ControlTable::PWMSlope.is_supported(&model), false);
     }
     #[test]
     fn to_size_xc330() {
@@ -424,10 +1023,123 @@ mod tests {
         assert_eq!(ControlTable::TorqueEnable.to_size(), 1);
     }
 
+    #[test]
+    fn to_protocol1_address() {
+        let model = DynamixelModel::default();
+        assert_eq!(
+            ControlTable::TorqueEnable.to_protocol1_address(&model),
+            Some(24)
+        );
+        assert_eq!(ControlTable::GoalPosition.to_protocol1_size(), Some(2));
+        assert_eq!(
+            ControlTable::OperatingMode.to_protocol1_address(&model),
+            None
+        );
+        assert_eq!(
+            ControlTable::PositionPGain.to_protocol1_address(&model),
+            Some(28)
+        );
+        // Compliance Slope on AX series
+        let model = DynamixelModel::Ax12A;
+        assert_eq!(
+            ControlTable::PositionPGain.to_protocol1_address(&model),
+            None
+        );
+        assert_eq!(
+            ControlTable::GoalPosition.to_protocol1_address(&model),
+            Some(30)
+        );
+    }
+
     #[test]
     fn to_unit_xc330() {
         let name = ControlTable::ModelNumber;
         assert_eq!(name.to_unit(&DynamixelModel::Xc330T181), 1.0);
         assert_eq!(ControlTable::PresentPWM.to_unit(&DynamixelModel::Xc330T181), 0.113);
     }
+
+    #[test]
+    fn model_number() {
+        assert_eq!(
+            DynamixelModel::from_model_number(1030),
+            Some(DynamixelModel::Xm430W210)
+        );
+        assert_eq!(
+            DynamixelModel::from_model_number(12),
+            Some(DynamixelModel::Ax12A)
+        );
+        // MX-28 is not registered
+        assert_eq!(DynamixelModel::from_model_number(29), None);
+        let model = DynamixelModel::Ph54_200S500;
+        let model_number = model.to_model_number();
+        assert_eq!(DynamixelModel::from_model_number(model_number), Some(model));
+    }
+
+    #[test]
+    fn to_unit_x_series() {
+        let current = ControlTable::PresentCurrent;
+        assert_eq!(current.to_unit(&DynamixelModel::Xm430W210), 2.69);
+        assert_eq!(current.to_unit(&DynamixelModel::Xh430V350), 1.34);
+        assert_eq!(current.to_unit(&DynamixelModel::Xl330M288), 1.0);
+        let velocity = ControlTable::GoalVelocity;
+        assert_eq!(velocity.to_unit(&DynamixelModel::Xm540W270), 0.229);
+    }
+
+    #[test]
+    fn to_unit_p_series() {
+        let model = DynamixelModel::Pm54_040S250;
+        assert_eq!(ControlTable::GoalVelocity.to_unit(&model), 0.01);
+        assert_eq!(ControlTable::ProfileAccleration.to_unit(&model), 1.0);
+        assert_eq!(model.position_resolution(), 502_834);
+        assert_eq!(model.home_position(), 0);
+    }
+
+    #[test]
+    fn is_supported() {
+        let current = ControlTable::GoalCurrent;
+        assert_eq!(current.is_supported(&DynamixelModel::Xl430W250), false);
+        assert_eq!(current.is_supported(&DynamixelModel::Xh540W150), true);
+        let slope = ControlTable::PWMSlope;
+        assert_eq!(slope.is_supported(&DynamixelModel::Xm430W350), false);
+        assert_eq!(slope.is_supported(&DynamixelModel::Xl330M077), true);
+        let torque = ControlTable::TorqueEnable;
+        assert_eq!(torque.is_supported(&DynamixelModel::TwoXc430W250), true);
+    }
+
+    #[test]
+    fn to_descriptor() {
+        let x = DynamixelModel::Xm430W350;
+        let p = DynamixelModel::Ph54_200S500;
+        let name = ControlTable::PresentPosition;
+        let descriptor = name.to_descriptor(&x);
+        assert_eq!(descriptor.address, 132);
+        assert_eq!(descriptor.size, 4);
+        assert_eq!(descriptor.area, MemoryArea::RAM);
+        assert_eq!(descriptor.access, Access::ReadOnly);
+        assert_eq!(descriptor.range, None);
+        let name = ControlTable::OperatingMode;
+        assert_eq!(name.to_area(&x), MemoryArea::EEPROM);
+        assert_eq!(name.to_access(), Access::ReadWrite);
+        assert_eq!(name.to_range(&x), Some((0, 16)));
+        let name = ControlTable::IndirectAddress1;
+        assert_eq!(name.to_area(&x), MemoryArea::RAM);
+        assert_eq!(name.to_area(&p), MemoryArea::EEPROM);
+        let name = ControlTable::GoalPosition;
+        assert_eq!(name.to_range(&x), Some((-1_048_575, 1_048_575)));
+        assert_eq!(name.to_range(&p), Some((-501_923, 501_923)));
+        assert_eq!(name.to_area(&p), MemoryArea::RAM);
+    }
+
+    #[test]
+    fn voltage_limit_range() {
+        let name = ControlTable::MaxVoltageLimit;
+        assert_eq!(name.to_range(&DynamixelModel::Xc330T181), Some((31, 70)));
+        assert_eq!(name.to_range(&DynamixelModel::Xl430W250), Some((60, 140)));
+        assert_eq!(name.to_range(&DynamixelModel::Xm430W210), Some((95, 160)));
+        assert_eq!(name.to_range(&DynamixelModel::Xh430V350), Some((110, 300)));
+        assert_eq!(
+            ControlTable::MinVoltageLimit.to_range(&DynamixelModel::Xh540V270),
+            Some((110, 300))
+        );
+    }
 }
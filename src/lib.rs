@@ -1,23 +1,42 @@
 #![no_std]
 //! This crate is for control dynamixel.
 //!
-#![allow(unused_imports)]
+//! `std` feature provides serial_port::SerialPortInterface and serial_port::StdClock for desktop applications.
+//! `embedded-io` and `embedded-hal-nb` features provide adapters in adapter module for MCU firmware.
+//! `async` feature provides async_control::AsyncDynamixelControl over embedded-io-async and embedded-hal-async.
+#[cfg(feature = "std")]
+extern crate std;
+
+pub mod adapter;
+#[cfg(feature = "async")]
+pub mod async_control;
 pub mod control_data;
 pub mod control_table;
 mod instruction;
 pub mod packet_handler;
+pub mod protocol1;
+mod registry;
+#[cfg(feature = "std")]
+pub mod serial_port;
 pub mod utils;
 pub use control_data::*;
+pub use control_table::Access;
 pub use control_table::ControlTable;
 pub use control_table::DynamixelModel;
+pub use control_table::MemoryArea;
+pub use control_table::RegisterDescriptor;
 pub use packet_handler::CommunicationResult;
-use packet_handler::MAX_PACKET_LEN;
+pub use packet_handler::ErrorBit;
+pub use packet_handler::ProtocolVersion;
+use packet_handler::MAX_ID;
 pub use utils::DegRad;
 
 use core::result::Result;
 use core::time::Duration;
-use heapless::Vec;
 use instruction::Instruction;
+use registry::DeviceRegistry;
+use utils::calc_tx_time_per_byte;
+use utils::round_to_i32;
 
 pub trait Interface {
     fn write_byte(&mut self, data: u8);
@@ -37,8 +56,9 @@ pub struct DynamixelControl<'a> {
     is_using: bool,
     packet_start_time: Duration,
     packet_timeout: Duration,
-    baudrate: u32,
     tx_time_per_byte: u64,
+    registry: DeviceRegistry,
+    echo_suppression: bool,
 }
 
 impl<'a> DynamixelControl<'a> {
@@ -50,9 +70,100 @@ impl<'a> DynamixelControl<'a> {
             is_using: false,
             packet_start_time: Duration::new(0, 0),
             packet_timeout: Duration::new(0, 0),
-            baudrate: baudrate,
-            tx_time_per_byte: ((1_000_000.0 * 8.0 + (baudrate as f32 - 1.0)) / baudrate as f32)
-                as u64,
+            tx_time_per_byte: calc_tx_time_per_byte(baudrate),
+            registry: DeviceRegistry::new(),
+            echo_suppression: false,
+        }
+    }
+
+    /// Protocol 2.0 is used by default.
+    /// This is used for the broadcast and the IDs not registered by set_id_protocol_version.
+    pub fn set_protocol_version(&mut self, version: ProtocolVersion) {
+        self.registry.protocol_version = version;
+    }
+
+    pub fn get_protocol_version(&self) -> ProtocolVersion {
+        self.registry.protocol_version
+    }
+
+    /// Register the protocol version of the device, so both versions can share the bus.
+    pub fn set_id_protocol_version(
+        &mut self,
+        id: u8,
+        version: ProtocolVersion,
+    ) -> Result<(), CommunicationResult> {
+        self.registry.set_id_protocol_version(id, version)
+    }
+
+    pub fn get_id_protocol_version(&self, id: u8) -> ProtocolVersion {
+        self.registry.get_id_protocol_version(id)
+    }
+
+    /// Register the model of the device to resolve its control table and units.
+    pub fn set_model(&mut self, id: u8, model: DynamixelModel) -> Result<(), CommunicationResult> {
+        self.registry.set_model(id, model)
+    }
+
+    /// DynamixelModel::default() is used for the IDs not registered by set_model.
+    pub fn get_model(&self, id: u8) -> DynamixelModel {
+        self.registry.get_model(id)
+    }
+
+    /// Validate the written value against the range of the item and the limits of the device before sending.
+    /// Disabled by default, because the limits are read from the device on the first write.
+    pub fn set_range_check(&mut self, enable: bool) {
+        self.registry.range_check = enable;
+    }
+
+    /// For single-wire TTL buses where TX and RX are tied together.
+    /// The transmitted bytes are read back and checked before receiving the status packet.
+    pub fn set_echo_suppression(&mut self, enable: bool) {
+        self.echo_suppression = enable;
+    }
+
+    /// Read the limit registers from the device and cache them.
+    pub fn read_limits(&mut self, id: u8) -> Result<DeviceLimits, CommunicationResult> {
+        if id > MAX_ID {
+            return Err(CommunicationResult::NotAvailable);
+        }
+        let mut limits = DeviceLimits::default();
+        let operating_mode = self.read_optional(id, ControlTable::OperatingMode)?;
+        if operating_mode == Some(3) {
+            limits.max_position = self.read_optional(id, ControlTable::MaxPositionLimit)?;
+            limits.min_position = self.read_optional(id, ControlTable::MinPositionLimit)?;
+        }
+        limits.current = self.read_optional(id, ControlTable::CurrentLimit)?;
+        limits.velocity = self.read_optional(id, ControlTable::VelocityLimit)?;
+        limits.pwm = self.read_optional(id, ControlTable::PWMLimit)?;
+        self.registry.cache_limits(id, limits);
+        Ok(limits)
+    }
+
+    /// Limits cached by read_limits.
+    pub fn get_limits(&self, id: u8) -> Option<DeviceLimits> {
+        self.registry.get_limits(id)
+    }
+
+    /// The cache is also cleared by writing EEPROM area of the device.
+    pub fn clear_limits(&mut self, id: u8) {
+        self.registry.clear_limits(id)
+    }
+
+    /// None if the device does not have the item.
+    fn read_optional(
+        &mut self,
+        id: u8,
+        data_name: ControlTable,
+    ) -> Result<Option<i32>, CommunicationResult> {
+        let result = match data_name.to_size() {
+            1 => self.read_1byte(id, data_name).map(|v| v as i32),
+            2 => self.read_2byte(id, data_name).map(|v| v as i32),
+            _ => self.read_4byte(id, data_name).map(|v| v as i32),
+        };
+        match result {
+            Ok(v) => Ok(Some(v)),
+            Err(CommunicationResult::NotAvailable) => Ok(None),
+            Err(e) => Err(e),
         }
     }
 
@@ -64,22 +175,59 @@ impl<'a> DynamixelControl<'a> {
         self.write_1byte(id, ControlTable::OperatingMode, data.to_value())
     }
 
+    pub fn get_drive_mode(&mut self, id: u8) -> Result<DriveMode, CommunicationResult> {
+        match self.read_1byte(id, ControlTable::DriveMode) {
+            Ok(v) => Ok(DriveMode::from_value(v)),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Drive Mode is in EEPROM area, so torque must be disabled.
+    pub fn set_drive_mode(&mut self, id: u8, mode: DriveMode) -> Result<(), CommunicationResult> {
+        self.write_1byte(id, ControlTable::DriveMode, mode.to_value())
+    }
+
+    /// Read Drive Mode, change it and write it back, so the other bits are kept.
+    /// e.g. dxl.update_drive_mode(id, |m| m.reverse(true))
+    pub fn update_drive_mode(
+        &mut self,
+        id: u8,
+        f: impl FnOnce(DriveMode) -> DriveMode,
+    ) -> Result<(), CommunicationResult> {
+        match self.get_drive_mode(id) {
+            Ok(v) => self.set_drive_mode(id, f(v)),
+            Err(e) => Err(e),
+        }
+    }
+
     pub fn set_led(&mut self, id: u8, data: u8) {
         self.send_write_packet(id, ControlTable::LED, &[data])
             .unwrap();
     }
 
+    /// Torque Enable is cached to refuse EEPROM writes while the torque is on.
     pub fn set_torque_enable(&mut self, id: u8, data: u8) -> Result<(), CommunicationResult> {
         self.write_1byte(id, ControlTable::TorqueEnable, data)
     }
 
+    /// Whether an instruction registered by reg_write is waiting for action.
+    pub fn get_registered_instruction(&mut self, id: u8) -> Result<bool, CommunicationResult> {
+        match self.read_1byte(id, ControlTable::RegisteredInstruction) {
+            Ok(v) => Ok(v == 1),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// position: rad
+    /// X series is 2π/4096 [rad/pulse] (0.0879 [deg/pulse]), not the rounded 0.088 [deg/pulse].
     pub fn get_present_position(&mut self, id: u8) -> Result<f32, CommunicationResult> {
+        let model = self.get_model(id);
         let result = self.read_4byte(id, ControlTable::PresentPosition);
         match result {
-            Ok(v) => Ok((v as f32 * ControlTable::PresentPosition.to_unit(&DynamixelModel::Xc330T181)
-                - dxl_consts::f32::HOME_POSITION)
-                .pulse2deg()
-                .deg2rad()),
+            Ok(v) => Ok((v as i32 - model.home_position()) as f32
+                / model.position_resolution() as f32
+                * 2.0
+                * core::f32::consts::PI),
             Err(e) => Err(e),
         }
     }
@@ -87,7 +235,7 @@ impl<'a> DynamixelControl<'a> {
     /// current: A
     pub fn set_goal_current(&mut self, id: u8, current: f32) -> Result<(), CommunicationResult> {
         let data: u16 = u16::from_le_bytes(
-            ((current / ControlTable::GoalCurrent.to_unit(&DynamixelModel::Xc330T181) * 1000.0) as i16).to_le_bytes(),
+            ((current / ControlTable::GoalCurrent.to_unit(&self.get_model(id)) * 1000.0) as i16).to_le_bytes(),
         );
         let result = self.write_2byte(id, ControlTable::GoalCurrent, data);
         // let result = self.send_2byte_write_packet(id, ControlTable::GoalCurrent, data);
@@ -96,40 +244,190 @@ impl<'a> DynamixelControl<'a> {
             Err(e) => Err(e),
         }
     }
+
+    /// velocity: rad/s
+    pub fn get_present_velocity(&mut self, id: u8) -> Result<f32, CommunicationResult> {
+        let unit = ControlTable::PresentVelocity.to_unit(&self.get_model(id));
+        match self.read_4byte(id, ControlTable::PresentVelocity) {
+            Ok(v) => Ok(v as i32 as f32 * unit / 60.0 * 2.0 * core::f32::consts::PI),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// current: A
+    pub fn get_present_current(&mut self, id: u8) -> Result<f32, CommunicationResult> {
+        let unit = ControlTable::PresentCurrent.to_unit(&self.get_model(id));
+        match self.read_2byte(id, ControlTable::PresentCurrent) {
+            Ok(v) => Ok(v as i16 as f32 * unit / 1000.0),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// pwm: %
+    pub fn get_present_pwm(&mut self, id: u8) -> Result<f32, CommunicationResult> {
+        let unit = ControlTable::PresentPWM.to_unit(&self.get_model(id));
+        match self.read_2byte(id, ControlTable::PresentPWM) {
+            Ok(v) => Ok(v as i16 as f32 * unit),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// voltage: V
+    pub fn get_present_input_voltage(&mut self, id: u8) -> Result<f32, CommunicationResult> {
+        let unit = ControlTable::PresentInputVoltage.to_unit(&self.get_model(id));
+        match self.read_2byte(id, ControlTable::PresentInputVoltage) {
+            Ok(v) => Ok(v as f32 * unit),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// temperature: degC
+    pub fn get_present_temperature(&mut self, id: u8) -> Result<f32, CommunicationResult> {
+        let unit = ControlTable::PresentTemperature.to_unit(&self.get_model(id));
+        match self.read_1byte(id, ControlTable::PresentTemperature) {
+            Ok(v) => Ok(v as f32 * unit),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// position: rad
+    pub fn set_goal_position(&mut self, id: u8, position: f32) -> Result<(), CommunicationResult> {
+        let model = self.get_model(id);
+        let data = round_to_i32(
+            position / (2.0 * core::f32::consts::PI) * model.position_resolution() as f32,
+        ) + model.home_position();
+        self.write_4byte(id, ControlTable::GoalPosition, data as u32)
+    }
+
+    /// velocity: rad/s
+    pub fn set_goal_velocity(&mut self, id: u8, velocity: f32) -> Result<(), CommunicationResult> {
+        let unit = ControlTable::GoalVelocity.to_unit(&self.get_model(id));
+        let data = round_to_i32(velocity / (2.0 * core::f32::consts::PI) * 60.0 / unit);
+        self.write_4byte(id, ControlTable::GoalVelocity, data as u32)
+    }
+
+    /// pwm: %
+    pub fn set_goal_pwm(&mut self, id: u8, pwm: f32) -> Result<(), CommunicationResult> {
+        let unit = ControlTable::GoalPWM.to_unit(&self.get_model(id));
+        let data = round_to_i32(pwm / unit) as i16;
+        self.write_2byte(id, ControlTable::GoalPWM, data as u16)
+    }
+
+    /// velocity: rad/s
+    /// Not for Time-based Profile, where the unit is ms.
+    pub fn set_profile_velocity(
+        &mut self,
+        id: u8,
+        velocity: f32,
+    ) -> Result<(), CommunicationResult> {
+        let unit = ControlTable::ProfileVelocity.to_unit(&self.get_model(id));
+        let data = round_to_i32(velocity / (2.0 * core::f32::consts::PI) * 60.0 / unit);
+        self.write_4byte(id, ControlTable::ProfileVelocity, data as u32)
+    }
+
+    /// acceleration: rad/s^2
+    /// Not for Time-based Profile, where the unit is ms.
+    pub fn set_profile_acceleration(
+        &mut self,
+        id: u8,
+        acceleration: f32,
+    ) -> Result<(), CommunicationResult> {
+        let unit = ControlTable::ProfileAccleration.to_unit(&self.get_model(id));
+        let data = round_to_i32(acceleration / (2.0 * core::f32::consts::PI) * 3600.0 / unit);
+        self.write_4byte(id, ControlTable::ProfileAccleration, data as u32)
+    }
+
+    pub fn set_position_p_gain(&mut self, id: u8, gain: u16) -> Result<(), CommunicationResult> {
+        self.write_2byte(id, ControlTable::PositionPGain, gain)
+    }
+
+    pub fn set_position_i_gain(&mut self, id: u8, gain: u16) -> Result<(), CommunicationResult> {
+        self.write_2byte(id, ControlTable::PositionIGain, gain)
+    }
+
+    pub fn set_position_d_gain(&mut self, id: u8, gain: u16) -> Result<(), CommunicationResult> {
+        self.write_2byte(id, ControlTable::PositionDGain, gain)
+    }
+
+    pub fn set_velocity_p_gain(&mut self, id: u8, gain: u16) -> Result<(), CommunicationResult> {
+        self.write_2byte(id, ControlTable::VelocityPgain, gain)
+    }
+
+    pub fn set_velocity_i_gain(&mut self, id: u8, gain: u16) -> Result<(), CommunicationResult> {
+        self.write_2byte(id, ControlTable::VelocityIGain, gain)
+    }
+
+    pub fn set_feedforward_1st_gain(
+        &mut self,
+        id: u8,
+        gain: u16,
+    ) -> Result<(), CommunicationResult> {
+        self.write_2byte(id, ControlTable::Feedforward1stGain, gain)
+    }
+
+    pub fn set_feedforward_2nd_gain(
+        &mut self,
+        id: u8,
+        gain: u16,
+    ) -> Result<(), CommunicationResult> {
+        self.write_2byte(id, ControlTable::Feedforward2ndGain, gain)
+    }
 }
 
 #[cfg(test)]
+#[allow(
+    clippy::assertions_on_constants,
+    clippy::bool_assert_comparison,
+    clippy::len_zero,
+    clippy::needless_range_loop,
+    clippy::unnecessary_cast,
+)]
 mod tests {
     use crate::control_data::*;
     use crate::packet_handler::CommunicationResult;
+    use crate::packet_handler::ErrorBit;
     use crate::packet_handler::Packet;
+    use crate::protocol1::Protocol1Packet;
     use crate::ControlTable;
     use crate::DynamixelModel;
     use crate::DynamixelControl;
     use crate::Instruction;
     use core::cell::RefCell;
+    use core::f32::consts::FRAC_PI_2;
+    use core::f32::consts::PI;
     use core::time::Duration;
     use heapless::Deque;
     use heapless::Vec;
 
     pub struct MockSerial {
-        rx_buf: Vec<u8, 256>,
+        pub(crate) rx_buf: Vec<u8, 256>,
         tx_buf: Deque<u8, 256>,
+        packet_pos: usize, // start of the last packet in rx_buf
+        echo: bool,        // TX and RX are tied together
+        collision: bool,   // Last byte of the echo is broken
     }
     impl MockSerial {
         pub fn new() -> Self {
             Self {
                 rx_buf: Vec::<u8, 256>::new(),
                 tx_buf: Deque::<u8, 256>::new(),
+                packet_pos: 0,
+                echo: false,
+                collision: false,
             }
         }
 
         fn set_test_tx_data(&mut self) {
+            let rx_buf = &self.rx_buf[self.packet_pos..];
+            if rx_buf.len() > Packet::Header2.to_pos() && rx_buf[Packet::Header2.to_pos()] != 0xFD {
+                self.set_protocol1_test_tx_data();
+                return;
+            }
             // For test ping
             if self.tx_buf.len() == 0
-                && self.rx_buf.len() > 8
-                && self.rx_buf[Packet::Instruction.to_pos()] == Instruction::Ping.into()
-                && self.rx_buf[Packet::Id.to_pos()] == 0x01
+                && rx_buf.len() > 8
+                && rx_buf[Packet::Instruction.to_pos()] == Instruction::Ping.into()
+                && rx_buf[Packet::Id.to_pos()] == 0x01
             {
                 // ID1(XM430-W210) : For Model Number 1030(0x0406), Version of Firmware 38(0x26)
                 // Instruction Packet ID : 1
@@ -141,12 +439,29 @@ mod tests {
                     self.tx_buf.push_back(data).unwrap();
                 }
             }
+            // For test broadcast ping
+            if self.tx_buf.len() == 0
+                && rx_buf.len() > 8
+                && rx_buf[Packet::Instruction.to_pos()] == Instruction::Ping.into()
+                && rx_buf[Packet::Id.to_pos()] == 0xFE
+            {
+                // ID1(XM430-W210) : For Model Number 1030(0x0406), Version of Firmware 38(0x26)
+                // ID2(XC330-T181) : For Model Number 1210(0x04BA), Version of Firmware 46(0x2E)
+                let res = [
+                    0xFF, 0xFF, 0xFD, 0x00, 0x01, 0x07, 0x00, 0x55, 0x00, 0x06, 0x04, 0x26, 0x65,
+                    0x5D, 0xFF, 0xFF, 0xFD, 0x00, 0x02, 0x07, 0x00, 0x55, 0x00, 0xBA, 0x04, 0x2E,
+                    0x6F, 0x64,
+                ];
+                for data in res {
+                    self.tx_buf.push_back(data).unwrap();
+                }
+            }
             // For test read(8byte)
             if self.tx_buf.len() == 0
-                && self.rx_buf.len() > 8
-                && self.rx_buf[Packet::Instruction.to_pos()] == Instruction::Read.into()
-                && self.rx_buf[Packet::Id.to_pos()] == 0x01
-                && self.rx_buf[Packet::Parameter0.to_pos()] == 0x00
+                && rx_buf.len() > 8
+                && rx_buf[Packet::Instruction.to_pos()] == Instruction::Read.into()
+                && rx_buf[Packet::Id.to_pos()] == 0x01
+                && rx_buf[Packet::Parameter0.to_pos()] == 0x00
             {
                 let res = [
                     0xFF, 0xFF, 0xFD, 0x00, 0x01, 0x0C, 0x00, 0x55, 0x00, 0x06, 0x04, 0x00, 0x00, 0x00,
@@ -158,10 +473,10 @@ mod tests {
             }
             // For test read(4byte)
             if self.tx_buf.len() == 0
-                && self.rx_buf.len() > 8
-                && self.rx_buf[Packet::Instruction.to_pos()] == Instruction::Read.into()
-                && self.rx_buf[Packet::Id.to_pos()] == 0x01
-                && self.rx_buf[Packet::Parameter0.to_pos()] == 0x84
+                && rx_buf.len() > 8
+                && rx_buf[Packet::Instruction.to_pos()] == Instruction::Read.into()
+                && rx_buf[Packet::Id.to_pos()] == 0x01
+                && rx_buf[Packet::Parameter0.to_pos()] == 0x84
             {
                 // ID1(XM430-W210) : Present Position(132, 0x0084, 4[byte]) = 166(0x000000A6)
                 let res = [
@@ -174,10 +489,10 @@ mod tests {
             }
             // For test read(2byte)
             if self.tx_buf.len() == 0
-                && self.rx_buf.len() > 8
-                && self.rx_buf[Packet::Instruction.to_pos()] == Instruction::Read.into()
-                && self.rx_buf[Packet::Id.to_pos()] == 0x01
-                && self.rx_buf[Packet::Parameter0.to_pos()] == 0x26
+                && rx_buf.len() > 8
+                && rx_buf[Packet::Instruction.to_pos()] == Instruction::Read.into()
+                && rx_buf[Packet::Id.to_pos()] == 0x01
+                && rx_buf[Packet::Parameter0.to_pos()] == 0x26
             {
                 // ID1(XC330-T181) : Current Limit(38, 0x0026, 2[byte]) = 888(0x0378)
                 let res = [
@@ -189,10 +504,10 @@ mod tests {
             }
             // For test read(1byte)
             if self.tx_buf.len() == 0
-                && self.rx_buf.len() > 8
-                && self.rx_buf[Packet::Instruction.to_pos()] == Instruction::Read.into()
-                && self.rx_buf[Packet::Id.to_pos()] == 0x01
-                && self.rx_buf[Packet::Parameter0.to_pos()] == 0x0B
+                && rx_buf.len() > 8
+                && rx_buf[Packet::Instruction.to_pos()] == Instruction::Read.into()
+                && rx_buf[Packet::Id.to_pos()] == 0x01
+                && rx_buf[Packet::Parameter0.to_pos()] == 0x0B
             {
                 // ID1(XC330-T181) : Operating Mode(11, 0x000B, 1[byte]) = 5(0x05)
                 let res = [
@@ -204,10 +519,10 @@ mod tests {
             }
             // For test write(4byte)
             if self.tx_buf.len() == 0
-                && self.rx_buf.len() > 15
-                && self.rx_buf[Packet::Instruction.to_pos()] == Instruction::Write.into()
-                && self.rx_buf[Packet::Id.to_pos()] == 0x01
-                && self.rx_buf[Packet::Parameter0.to_pos()] == 0x74
+                && rx_buf.len() > 15
+                && rx_buf[Packet::Instruction.to_pos()] == Instruction::Write.into()
+                && rx_buf[Packet::Id.to_pos()] == 0x01
+                && rx_buf[Packet::Parameter0.to_pos()] == 0x74
             {
                 // ID1(XM430-W210) : Write 512(0x00000200) to Goal Position(116, 0x0074, 4[byte])
                 let res = [
@@ -219,10 +534,10 @@ mod tests {
             }
             // For test write(2byte)
             if self.tx_buf.len() == 0
-                && self.rx_buf.len() > 13
-                && self.rx_buf[Packet::Instruction.to_pos()] == Instruction::Write.into()
-                && self.rx_buf[Packet::Id.to_pos()] == 0x01
-                && self.rx_buf[Packet::Parameter0.to_pos()] == 0x26
+                && rx_buf.len() > 13
+                && rx_buf[Packet::Instruction.to_pos()] == Instruction::Write.into()
+                && rx_buf[Packet::Id.to_pos()] == 0x01
+                && rx_buf[Packet::Parameter0.to_pos()] == 0x26
             {
                 // ID1(XC330-T181) : Current Limit(38, 0x0026, 2[byte]) = 888(0x0378)
                 let res = [
@@ -234,10 +549,10 @@ mod tests {
             }
             // For test write(1byte)
             if self.tx_buf.len() == 0
-                && self.rx_buf.len() > 12
-                && self.rx_buf[Packet::Instruction.to_pos()] == Instruction::Write.into()
-                && self.rx_buf[Packet::Id.to_pos()] == 0x01
-                && self.rx_buf[Packet::Parameter0.to_pos()] == 0x1F
+                && rx_buf.len() > 12
+                && rx_buf[Packet::Instruction.to_pos()] == Instruction::Write.into()
+                && rx_buf[Packet::Id.to_pos()] == 0x01
+                && rx_buf[Packet::Parameter0.to_pos()] == 0x1F
             {
                 // ID1(XC330-T181) : Temperature Limit(31, 0x001F, 1[byte]) = 80(0x50)
                 let res = [
@@ -247,13 +562,217 @@ mod tests {
                     self.tx_buf.push_back(data).unwrap();
                 }
             }
-            // For test reboot and factory reset
+            // For test registered instruction(1byte)
+            if self.tx_buf.len() == 0
+                && rx_buf.len() > 8
+                && rx_buf[Packet::Instruction.to_pos()] == Instruction::Read.into()
+                && rx_buf[Packet::Id.to_pos()] == 0x01
+                && rx_buf[Packet::Parameter0.to_pos()] == 0x45
+            {
+                // ID1(XM430-W210) : Registered Instruction(69, 0x0045, 1[byte]) = 1(0x01)
+                let res = [
+                    0xFF, 0xFF, 0xFD, 0x00, 0x01, 0x05, 0x00, 0x55, 0x00, 0x01, 0x56, 0xA1,
+                ];
+                for data in res {
+                    self.tx_buf.push_back(data).unwrap();
+                }
+            }
+            // For test backup ready(1byte)
+            if self.tx_buf.len() == 0
+                && rx_buf.len() > 8
+                && rx_buf[Packet::Instruction.to_pos()] == Instruction::Read.into()
+                && rx_buf[Packet::Id.to_pos()] == 0x01
+                && rx_buf[Packet::Parameter0.to_pos()] == 0x93
+            {
+                // ID1(XM430-W210) : Backup Ready(147, 0x0093, 1[byte]) = 1(0x01)
+                let res = [
+                    0xFF, 0xFF, 0xFD, 0x00, 0x01, 0x05, 0x00, 0x55, 0x00, 0x01, 0x56, 0xA1,
+                ];
+                for data in res {
+                    self.tx_buf.push_back(data).unwrap();
+                }
+            }
+            // For test status error
+            if self.tx_buf.len() == 0
+                && rx_buf.len() > 12
+                && rx_buf[Packet::Instruction.to_pos()] == Instruction::Write.into()
+                && rx_buf[Packet::Id.to_pos()] == 0x01
+                && rx_buf[Packet::Parameter0.to_pos()] == 0x0B
+            {
+                // ID1(XC330-T181) : Operating Mode(11, 0x000B, 1[byte]) = 2 is Data Range Error
+                let res = [
+                    0xFF, 0xFF, 0xFD, 0x00, 0x01, 0x04, 0x00, 0x55, 0x04, 0xBA, 0x8C,
+                ];
+                for data in res {
+                    self.tx_buf.push_back(data).unwrap();
+                }
+            }
+            // For test status alert
+            if self.tx_buf.len() == 0
+                && rx_buf.len() > 8
+                && rx_buf[Packet::Instruction.to_pos()] == Instruction::Read.into()
+                && rx_buf[Packet::Id.to_pos()] == 0x01
+                && rx_buf[Packet::Parameter0.to_pos()] == 0x92
+            {
+                // ID1(XC330-T181) : Present Temperature(146, 0x0092, 1[byte]) = 80(0x50) with Alert
+                let res = [
+                    0xFF, 0xFF, 0xFD, 0x00, 0x01, 0x05, 0x00, 0x55, 0x80, 0x50, 0xBA, 0xA0,
+                ];
+                for data in res {
+                    self.tx_buf.push_back(data).unwrap();
+                }
+            }
+            // For test P series
+            if self.tx_buf.len() == 0
+                && rx_buf.len() > 9
+                && rx_buf[Packet::Instruction.to_pos()] == Instruction::Read.into()
+                && rx_buf[Packet::Id.to_pos()] == 0x01
+                && rx_buf[Packet::Parameter0.to_pos()] == 0x44
+                && rx_buf[Packet::Parameter0.to_pos() + 1] == 0x02
+            {
+                // ID1(PH54-200-S500-R) : Present Position(580, 0x0244, 4[byte]) = 250961(0x0003D451)
+                let res = [
+                    0xFF, 0xFF, 0xFD, 0x00, 0x01, 0x08, 0x00, 0x55, 0x00, 0x51, 0xD4, 0x03, 0x00,
+                    0xB7, 0xE8,
+                ];
+                for data in res {
+                    self.tx_buf.push_back(data).unwrap();
+                }
+            }
+            // For test hardware error status
+            if self.tx_buf.len() == 0
+                && rx_buf.len() > 8
+                && rx_buf[Packet::Instruction.to_pos()] == Instruction::Read.into()
+                && rx_buf[Packet::Id.to_pos()] == 0x01
+                && rx_buf[Packet::Parameter0.to_pos()] == 0x46
+            {
+                // ID1(XC330-T181) : Hardware Error Status(70, 0x0046, 1[byte]) = 0x24 with Alert
+                let res = [
+                    0xFF, 0xFF, 0xFD, 0x00, 0x01, 0x05, 0x00, 0x55, 0x80, 0x24, 0x82, 0xA1,
+                ];
+                for data in res {
+                    self.tx_buf.push_back(data).unwrap();
+                }
+            }
+            // For test limits
+            if self.tx_buf.len() == 0
+                && rx_buf.len() > 8
+                && rx_buf[Packet::Instruction.to_pos()] == Instruction::Read.into()
+                && rx_buf[Packet::Id.to_pos()] == 0x01
+                && (rx_buf[Packet::Parameter0.to_pos()] == 0x2C
+                    || rx_buf[Packet::Parameter0.to_pos()] == 0x24)
+            {
+                // ID1(XC330-T181) : Velocity Limit(44, 0x002C, 4[byte]) = 445(0x000001BD)
+                // ID1(XC330-T181) : PWM Limit(36, 0x0024, 2[byte]) = 885(0x0375)
+                let res: &[u8] = if rx_buf[Packet::Parameter0.to_pos()] == 0x2C {
+                    &[
+                        0xFF, 0xFF, 0xFD, 0x00, 0x01, 0x08, 0x00, 0x55, 0x00, 0xBD, 0x01, 0x00,
+                        0x00, 0x9E, 0x1C,
+                    ]
+                } else {
+                    &[
+                        0xFF, 0xFF, 0xFD, 0x00, 0x01, 0x06, 0x00, 0x55, 0x00, 0x75, 0x03, 0xCA,
+                        0xE5,
+                    ]
+                };
+                for data in res {
+                    self.tx_buf.push_back(*data).unwrap();
+                }
+            }
+            // For test present values
+            if self.tx_buf.len() == 0
+                && rx_buf.len() > 8
+                && rx_buf[Packet::Instruction.to_pos()] == Instruction::Read.into()
+                && rx_buf[Packet::Id.to_pos()] == 0x02
+            {
+                // ID2(XC330-T181) : Present Velocity(128, 0x0080, 4[byte]) = -100(0xFFFFFF9C)
+                // ID2(XC330-T181) : Present Current(126, 0x007E, 2[byte]) = 200(0x00C8)
+                // ID2(XC330-T181) : Present PWM(124, 0x007C, 2[byte]) = -442(0xFE46)
+                // ID2(XC330-T181) : Present Input Voltage(144, 0x0090, 2[byte]) = 50(0x0032)
+                // ID2(XC330-T181) : Present Temperature(146, 0x0092, 1[byte]) = 35(0x23)
+                let res: &[u8] = match rx_buf[Packet::Parameter0.to_pos()] {
+                    0x80 => &[
+                        0xFF, 0xFF, 0xFD, 0x00, 0x02, 0x08, 0x00, 0x55, 0x00, 0x9C, 0xFF, 0xFF,
+                        0xFF, 0x27, 0x0E,
+                    ],
+                    0x7E => &[
+                        0xFF, 0xFF, 0xFD, 0x00, 0x02, 0x06, 0x00, 0x55, 0x00, 0xC8, 0x00, 0xFA,
+                        0x68,
+                    ],
+                    0x7C => &[
+                        0xFF, 0xFF, 0xFD, 0x00, 0x02, 0x06, 0x00, 0x55, 0x00, 0x46, 0xFE, 0xF7,
+                        0xCE,
+                    ],
+                    0x90 => &[
+                        0xFF, 0xFF, 0xFD, 0x00, 0x02, 0x06, 0x00, 0x55, 0x00, 0x32, 0x00, 0xF5,
+                        0xF4,
+                    ],
+                    0x92 => &[
+                        0xFF, 0xFF, 0xFD, 0x00, 0x02, 0x05, 0x00, 0x55, 0x00, 0x23, 0x9A, 0x29,
+                    ],
+                    _ => &[],
+                };
+                for data in res {
+                    self.tx_buf.push_back(*data).unwrap();
+                }
+            }
+            // For test goal values
+            if self.tx_buf.len() == 0
+                && rx_buf.len() > 12
+                && rx_buf[Packet::Instruction.to_pos()] == Instruction::Write.into()
+                && rx_buf[Packet::Id.to_pos()] == 0x02
+            {
+                let res = [
+                    0xFF, 0xFF, 0xFD, 0x00, 0x02, 0x04, 0x00, 0x55, 0x00, 0x29, 0x0C,
+                ];
+                for data in res {
+                    self.tx_buf.push_back(data).unwrap();
+                }
+            }
+            // For test drive mode
+            if self.tx_buf.len() == 0
+                && rx_buf.len() > 8
+                && rx_buf[Packet::Id.to_pos()] == 0x01
+                && rx_buf[Packet::Parameter0.to_pos()] == 0x0A
+            {
+                // ID1(XC330-T181) : Drive Mode(10, 0x000A, 1[byte]) = 4(0x04)
+                let is_read = rx_buf[Packet::Instruction.to_pos()] == Instruction::Read.into();
+                let res: &[u8] = if is_read {
+                    &[
+                        0xFF, 0xFF, 0xFD, 0x00, 0x01, 0x05, 0x00, 0x55, 0x00, 0x04, 0x48, 0xA1,
+                    ]
+                } else {
+                    &[
+                        0xFF, 0xFF, 0xFD, 0x00, 0x01, 0x04, 0x00, 0x55, 0x00, 0xA1, 0x0C,
+                    ]
+                };
+                for data in res {
+                    self.tx_buf.push_back(*data).unwrap();
+                }
+            }
+            // For test reg write and action
+            if self.tx_buf.len() == 0
+                && rx_buf.len() > 8
+                && rx_buf[Packet::Id.to_pos()] == 0x01
+                && (rx_buf[Packet::Instruction.to_pos()] == Instruction::RegWrite.into()
+                    || rx_buf[Packet::Instruction.to_pos()] == Instruction::Action.into())
+            {
+                let res = [
+                    0xFF, 0xFF, 0xFD, 0x00, 0x01, 0x04, 0x00, 0x55, 0x00, 0xA1, 0x0C,
+                ];
+                for data in res {
+                    self.tx_buf.push_back(data).unwrap();
+                }
+            }
+            // For test reboot, factory reset, clear and control table backup
             if self.tx_buf.len() == 0
-                && self.rx_buf.len() > 8
-                && self.rx_buf[Packet::Id.to_pos()] == 0x01
-                && (self.rx_buf[Packet::Instruction.to_pos()] == Instruction::Reboot.into()
-                    || self.rx_buf[Packet::Instruction.to_pos()]
-                        == Instruction::FactoryReset.into())
+                && rx_buf.len() > 8
+                && rx_buf[Packet::Id.to_pos()] == 0x01
+                && (rx_buf[Packet::Instruction.to_pos()] == Instruction::Reboot.into()
+                    || rx_buf[Packet::Instruction.to_pos()] == Instruction::FactoryReset.into()
+                    || rx_buf[Packet::Instruction.to_pos()] == Instruction::Clear.into()
+                    || rx_buf[Packet::Instruction.to_pos()]
+                        == Instruction::ControlTableBackup.into())
             {
                 let res = [
                     0xFF, 0xFF, 0xFD, 0x00, 0x01, 0x04, 0x00, 0x55, 0x00, 0xA1, 0x0C,
@@ -262,10 +781,115 @@ mod tests {
                     self.tx_buf.push_back(data).unwrap();
                 }
             }
+            // For test Protocol 1.0 device on the same bus
+            if self.tx_buf.is_empty()
+                && rx_buf.len() > 8
+                && rx_buf[Packet::Instruction.to_pos()] == Instruction::Write.into()
+                && rx_buf[Packet::Id.to_pos()] == 0x03
+            {
+                let res = [
+                    0xFF, 0xFF, 0xFD, 0x00, 0x03, 0x04, 0x00, 0x55, 0x00, 0x52, 0x8C,
+                ];
+                for data in res {
+                    self.tx_buf.push_back(data).unwrap();
+                }
+            }
+            if self.tx_buf.is_empty()
+                && rx_buf.len() > 8
+                && rx_buf[Packet::Instruction.to_pos()] == Instruction::BulkRead.into()
+                && rx_buf[Packet::Parameter0.to_pos()] == 0x03
+            {
+                // ID3(XM430-W210) : Present Position(132, 0x0084, 4[byte]) = 166(0x000000A6)
+                let res = [
+                    0xFF, 0xFF, 0xFD, 0x00, 0x03, 0x08, 0x00, 0x55, 0x00, 0xA6, 0x00, 0x00, 0x00,
+                    0x4F, 0x4C,
+                ];
+                for data in res {
+                    self.tx_buf.push_back(data).unwrap();
+                }
+            }
+            // For test bulk read
+            if self.tx_buf.len() == 0
+                && rx_buf.len() > 8
+                && rx_buf[Packet::Instruction.to_pos()] == Instruction::BulkRead.into()
+            {
+                // ID1(XM430-W210) : Present Position(132, 0x0084, 4[byte]) = 166(0x000000A6)
+                // ID2(XC330-T181) : Current Limit(38, 0x0026, 2[byte]) = 888(0x0378)
+                let res = [
+                    0xFF, 0xFF, 0xFD, 0x00, 0x01, 0x08, 0x00, 0x55, 0x00, 0xA6, 0x00, 0x00, 0x00,
+                    0x8C, 0xC0, 0xFF, 0xFF, 0xFD, 0x00, 0x02, 0x06, 0x00, 0x55, 0x00, 0x78, 0x03,
+                    0xF9, 0x48,
+                ];
+                for data in res {
+                    self.tx_buf.push_back(data).unwrap();
+                }
+            }
+            // For test fast sync read with broken data
+            if self.tx_buf.is_empty()
+                && rx_buf.len() > 8
+                && rx_buf[Packet::Instruction.to_pos()] == Instruction::FastSyncRead.into()
+                && rx_buf[Packet::Parameter0.to_pos()] == 0x80
+            {
+                // ID1 : Present Velocity(128, 0x0080, 4[byte]) is broken after its CRC
+                // ID2 : Present Velocity(128, 0x0080, 4[byte]) = 2,079(0x0000081F)
+                let res = [
+                    0xFF, 0xFF, 0xFD, 0x00, 0xFE, 0x11, 0x00, 0x55, 0x00, 0x01, 0xA7, 0x00, 0x00,
+                    0x00, 0x74, 0x3B, 0x00, 0x02, 0x1F, 0x08, 0x00, 0x00, 0x62, 0x76,
+                ];
+                for data in res {
+                    self.tx_buf.push_back(data).unwrap();
+                }
+            }
+            // For test fast sync read
+            if self.tx_buf.len() == 0
+                && rx_buf.len() > 8
+                && rx_buf[Packet::Instruction.to_pos()] == Instruction::FastSyncRead.into()
+            {
+                // ID1(XM430-W210) : Present Position(132, 0x0084, 4[byte]) = 166(0x000000A6)
+                // ID2(XM430-W210) : Present Position(132, 0x0084, 4[byte]) = 2,079(0x0000081F)
+                let res = [
+                    0xFF, 0xFF, 0xFD, 0x00, 0xFE, 0x11, 0x00, 0x55, 0x00, 0x01, 0xA6, 0x00, 0x00,
+                    0x00, 0x74, 0x3B, 0x00, 0x02, 0x1F, 0x08, 0x00, 0x00, 0x1C, 0x36,
+                ];
+                for data in res {
+                    self.tx_buf.push_back(data).unwrap();
+                }
+            }
+            // For test fast bulk read with broken data
+            if self.tx_buf.is_empty()
+                && rx_buf.len() > 9
+                && rx_buf[Packet::Instruction.to_pos()] == Instruction::FastBulkRead.into()
+                && rx_buf[Packet::Parameter0.to_pos() + 1] == 0x80
+            {
+                // ID1 : Present Velocity(128, 0x0080, 4[byte]) is broken after its CRC
+                // ID2(XC330-T181) : Current Limit(38, 0x0026, 2[byte]) = 888(0x0378)
+                let res = [
+                    0xFF, 0xFF, 0xFD, 0x00, 0xFE, 0x0F, 0x00, 0x55, 0x00, 0x01, 0xA7, 0x00, 0x00,
+                    0x00, 0x34, 0x7B, 0x00, 0x02, 0x78, 0x03, 0xD0, 0xC4,
+                ];
+                for data in res {
+                    self.tx_buf.push_back(data).unwrap();
+                }
+            }
+            // For test fast bulk read
+            if self.tx_buf.len() == 0
+                && rx_buf.len() > 8
+                && rx_buf[Packet::Instruction.to_pos()] == Instruction::FastBulkRead.into()
+            {
+                // ID1(XM430-W210) : Present Position(132, 0x0084, 4[byte]) = 166(0x000000A6)
+                // ID2(XC330-T181) : Current Limit(38, 0x0026, 2[byte]) = 888(0x0378)
+                let res = [
+                    0xFF, 0xFF, 0xFD, 0x00, 0xFE, 0x0F, 0x00, 0x55, 0x00, 0x01, 0xA6, 0x00, 0x00,
+                    0x00, 0x34, 0x7B, 0x00, 0x02, 0x78, 0x03, 0xC7, 0x24,
+                ];
+                for data in res {
+                    self.tx_buf.push_back(data).unwrap();
+                }
+            }
             // For test sync read
             if self.tx_buf.len() == 0
-                && self.rx_buf.len() > 8
-                && self.rx_buf[Packet::Instruction.to_pos()] == Instruction::SyncRead.into()
+                && rx_buf.len() > 8
+                && rx_buf[Packet::Instruction.to_pos()] == Instruction::SyncRead.into()
             {
                 let res = [
                     0xFF, 0xFF, 0xFD, 0x00, 0x01, 0x08, 0x00, 0x55, 0x00, 0xA6, 0x00, 0x00, 0x00,
@@ -277,6 +901,74 @@ mod tests {
                 }
             }
         }
+        fn set_protocol1_test_tx_data(&mut self) {
+            let rx_buf = &self.rx_buf[self.packet_pos..];
+            if rx_buf.len() <= Protocol1Packet::Instruction.to_pos() {
+                return;
+            }
+            let mut res = Vec::<u8, 256>::new();
+            // For test ping
+            if rx_buf[Protocol1Packet::Instruction.to_pos()] == Instruction::Ping.into()
+                && rx_buf[Protocol1Packet::Id.to_pos()] == 0x01
+            {
+                res.extend_from_slice(&[0xFF, 0xFF, 0x01, 0x02, 0x00, 0xFC])
+                    .unwrap();
+            }
+            // For test read model number
+            if rx_buf[Protocol1Packet::Instruction.to_pos()] == Instruction::Read.into()
+                && rx_buf[Protocol1Packet::Id.to_pos()] == 0x01
+                && rx_buf[Protocol1Packet::Parameter0.to_pos()] == 0x00
+            {
+                // ID1(AX-12A) : Model Number 12(0x000C), Version of Firmware 24(0x18)
+                res.extend_from_slice(&[0xFF, 0xFF, 0x01, 0x05, 0x00, 0x0C, 0x00, 0x18, 0xD5])
+                    .unwrap();
+            }
+            // For test read(2byte)
+            if rx_buf[Protocol1Packet::Instruction.to_pos()] == Instruction::Read.into()
+                && rx_buf[Protocol1Packet::Id.to_pos()] == 0x01
+                && rx_buf[Protocol1Packet::Parameter0.to_pos()] == 0x24
+            {
+                // ID1(AX-12A) : Present Position(36, 0x24, 2[byte]) = 512(0x0200)
+                res.extend_from_slice(&[0xFF, 0xFF, 0x01, 0x04, 0x00, 0x00, 0x02, 0xF8])
+                    .unwrap();
+            }
+            // For test write
+            if rx_buf[Protocol1Packet::Instruction.to_pos()] == Instruction::Write.into()
+                && rx_buf[Protocol1Packet::Id.to_pos()] == 0x01
+            {
+                res.extend_from_slice(&[0xFF, 0xFF, 0x01, 0x02, 0x00, 0xFC])
+                    .unwrap();
+            }
+            // For test status error
+            if rx_buf[Protocol1Packet::Instruction.to_pos()] == Instruction::Write.into()
+                && rx_buf[Protocol1Packet::Id.to_pos()] == 0x02
+            {
+                // Range Error
+                res.extend_from_slice(&[0xFF, 0xFF, 0x02, 0x02, 0x08, 0xF3])
+                    .unwrap();
+            }
+            // For test hardware error
+            if rx_buf[Protocol1Packet::Instruction.to_pos()] == Instruction::Read.into()
+                && rx_buf[Protocol1Packet::Id.to_pos()] == 0x02
+            {
+                // Overheating and Overload Error
+                res.extend_from_slice(&[0xFF, 0xFF, 0x02, 0x02, 0x24, 0xD7])
+                    .unwrap();
+            }
+            // For test bulk read
+            if rx_buf[Protocol1Packet::Instruction.to_pos()] == Instruction::BulkRead.into() {
+                // ID1(MX-28) : Present Position(36, 0x24, 2[byte]) = 512(0x0200)
+                // ID2(MX-28) : Present Temperature(43, 0x2B, 1[byte]) = 40(0x28)
+                res.extend_from_slice(&[
+                    0xFF, 0xFF, 0x01, 0x04, 0x00, 0x00, 0x02, 0xF8, 0xFF, 0xFF, 0x02, 0x03, 0x00,
+                    0x28, 0xD2,
+                ])
+                .unwrap();
+            }
+            for data in res {
+                self.tx_buf.push_back(data).unwrap();
+            }
+        }
     }
     impl crate::Interface for MockSerial {
         fn write_byte(&mut self, data: u8) {
@@ -285,10 +977,22 @@ mod tests {
         }
 
         fn write_bytes(&mut self, data: &[u8]) {
+            self.packet_pos = self.rx_buf.len();
             for d in data {
                 self.rx_buf.push(*d).unwrap();
             }
             self.set_test_tx_data();
+            if self.echo {
+                // Echo comes before the status packet
+                for (i, d) in data.iter().enumerate().rev() {
+                    let d = if self.collision && i == data.len() - 1 {
+                        !*d
+                    } else {
+                        *d
+                    };
+                    self.tx_buf.push_front(d).unwrap();
+                }
+            }
         }
 
         fn read_byte(&mut self) -> Option<u8> {
@@ -328,6 +1032,24 @@ mod tests {
         }
     }
 
+    /// Goes on 1ms every time it is read, for the tests waiting until the packet timeout.
+    pub struct TickingClock {
+        clock: MockClock,
+    }
+    impl TickingClock {
+        pub fn new() -> Self {
+            Self {
+                clock: MockClock::new(),
+            }
+        }
+    }
+    impl crate::Clock for TickingClock {
+        fn get_current_time(&self) -> Duration {
+            self.clock.tick();
+            self.clock.time_elasped.clone().into_inner()
+        }
+    }
+
     #[test]
     fn set_led_xc330() {
         let mut mock_uart = MockSerial::new();
@@ -357,6 +1079,7 @@ mod tests {
             }
             Err(_) => assert!(false),
         }
+        assert_eq!(dxl.get_model(1), DynamixelModel::Xm430W210);
 
         assert_eq!(
             *mock_uart.rx_buf,
@@ -364,6 +1087,24 @@ mod tests {
         );
     }
 
+    #[test]
+    fn broadcast_ping() {
+        let mut mock_uart = MockSerial::new();
+        let mock_clock = TickingClock::new();
+        let mut dxl = DynamixelControl::new(&mut mock_uart, &mock_clock, 115200);
+
+        let result = dxl.broadcast_ping();
+        assert_eq!(dxl.get_model(1), DynamixelModel::Xm430W210);
+        assert_eq!(dxl.get_model(2), DynamixelModel::Xc330T181);
+
+        assert_eq!(
+            *mock_uart.rx_buf,
+            [0xFF, 0xFF, 0xFD, 0x00, 0xFE, 0x03, 0x00, 0x01, 0x31, 0x42]
+        );
+        assert_eq!(result.is_ok(), true);
+        assert_eq!(*result.unwrap(), [(1, 0x0406, 0x26), (2, 0x04BA, 0x2E)]);
+    }
+
     #[test]
     fn read() {
         // ID1(XM430-W210) : Present Position(132, 0x0084, 4[byte]) = 166(0x000000A6)
@@ -499,6 +1240,273 @@ mod tests {
         );
     }
 
+    #[test]
+    fn status_error() {
+        // ID1(XC330-T181) : Operating Mode(11, 0x000B, 1[byte]) = 2 is Data Range Error
+        let mut mock_uart = MockSerial::new();
+        let mock_clock = MockClock::new();
+        let mut dxl = DynamixelControl::new(&mut mock_uart, &mock_clock, 115200);
+        let result = dxl.write_1byte(1, ControlTable::OperatingMode, 2);
+        assert_eq!(
+            result,
+            Err(CommunicationResult::StatusError {
+                error: Ok(ErrorBit::ErrDataRange),
+                alert: false
+            })
+        );
+    }
+
+    #[test]
+    fn refuse_write() {
+        let mut mock_uart = MockSerial::new();
+        let mock_clock = MockClock::new();
+        let mut dxl = DynamixelControl::new(&mut mock_uart, &mock_clock, 115200);
+        let result = dxl.write_4byte(1, ControlTable::PresentPosition, 0);
+        assert_eq!(result, Err(CommunicationResult::ReadOnly));
+        dxl.set_torque_enable(2, 1).unwrap();
+        let result = dxl.write_1byte(2, ControlTable::OperatingMode, 3);
+        assert_eq!(result, Err(CommunicationResult::TorqueEnabled));
+        let result = dxl.send_1byte_write_packet(2, ControlTable::OperatingMode, 3);
+        assert_eq!(result, Err(CommunicationResult::TorqueEnabled));
+        dxl.set_torque_enable(2, 0).unwrap();
+        assert_eq!(dxl.write_1byte(2, ControlTable::OperatingMode, 3), Ok(()));
+        // Torque Enable is not read back
+        assert_eq!(
+            mock_uart.rx_buf[..13],
+            [0xFF, 0xFF, 0xFD, 0x00, 0x02, 0x06, 0x00, 0x03, 0x40, 0x00, 0x01, 0xEB, 0x65]
+        );
+        assert_eq!(
+            mock_uart.rx_buf[13..26],
+            [0xFF, 0xFF, 0xFD, 0x00, 0x02, 0x06, 0x00, 0x03, 0x40, 0x00, 0x00, 0xEE, 0xE5]
+        );
+    }
+
+    #[test]
+    fn range_check() {
+        let mut mock_uart = MockSerial::new();
+        let mock_clock = MockClock::new();
+        let mut dxl = DynamixelControl::new(&mut mock_uart, &mock_clock, 115200);
+        dxl.set_range_check(true);
+        // Out of the range of the item
+        let result = dxl.write_1byte(1, ControlTable::OperatingMode, 17);
+        assert_eq!(result, Err(CommunicationResult::OutOfRange));
+        // Out of Current Limit read from the device
+        let data = u16::from_le_bytes((-1000i16).to_le_bytes());
+        let result = dxl.send_2byte_write_packet(1, ControlTable::GoalCurrent, data);
+        assert_eq!(result, Err(CommunicationResult::OutOfRange));
+        // Operating Mode is Current-based Position Control Mode
+        assert_eq!(
+            dxl.get_limits(1),
+            Some(DeviceLimits {
+                max_position: None,
+                min_position: None,
+                current: Some(888),
+                velocity: Some(445),
+                pwm: Some(885),
+            })
+        );
+        // Cached limits are used
+        let result = dxl.send_2byte_write_packet(1, ControlTable::GoalCurrent, 500);
+        assert_eq!(result.is_ok(), true);
+        assert_eq!(
+            mock_uart.rx_buf[56..],
+            [0xFF, 0xFF, 0xFD, 0x00, 0x01, 0x07, 0x00, 0x03, 0x66, 0x00, 0xF4, 0x01, 0x41, 0x9D]
+        );
+    }
+
+    #[test]
+    fn get_present_values() {
+        let mut mock_uart = MockSerial::new();
+        let mock_clock = MockClock::new();
+        let mut dxl = DynamixelControl::new(&mut mock_uart, &mock_clock, 115200);
+        let result = dxl.get_present_velocity(2).unwrap();
+        assert!((result - -2.398).abs() < 0.001);
+        let result = dxl.get_present_current(2).unwrap();
+        assert!((result - 0.2).abs() < 0.001);
+        let result = dxl.get_present_pwm(2).unwrap();
+        assert!((result - -49.946).abs() < 0.001);
+        let result = dxl.get_present_input_voltage(2).unwrap();
+        assert!((result - 5.0).abs() < 0.001);
+        let result = dxl.get_present_temperature(2).unwrap();
+        assert!((result - 35.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn set_goal_values() {
+        let mut mock_uart = MockSerial::new();
+        let mock_clock = MockClock::new();
+        let mut dxl = DynamixelControl::new(&mut mock_uart, &mock_clock, 115200);
+        // 3072
+        dxl.set_goal_position(2, FRAC_PI_2).unwrap();
+        // 1 [rev/s] = 262 * 0.229 [rev/min]
+        dxl.set_goal_velocity(2, 2.0 * PI).unwrap();
+        // 442 * 0.113 [%]
+        dxl.set_goal_pwm(2, 50.0).unwrap();
+        // 0.5 [rev/s] = 131 * 0.229 [rev/min]
+        dxl.set_profile_velocity(2, PI).unwrap();
+        // 1 [rev/s^2] = 17 * 214.577 [rev/min^2]
+        dxl.set_profile_acceleration(2, 2.0 * PI).unwrap();
+        dxl.set_position_p_gain(2, 800).unwrap();
+        assert_eq!(
+            mock_uart.rx_buf[..16],
+            [
+                0xFF, 0xFF, 0xFD, 0x00, 0x02, 0x09, 0x00, 0x03, 0x74, 0x00, 0x00, 0x0C, 0x00, 0x00,
+                0x2D, 0xA9
+            ]
+        );
+        assert_eq!(
+            mock_uart.rx_buf[16..32],
+            [
+                0xFF, 0xFF, 0xFD, 0x00, 0x02, 0x09, 0x00, 0x03, 0x68, 0x00, 0x06, 0x01, 0x00, 0x00,
+                0xD7, 0x71
+            ]
+        );
+        assert_eq!(
+            mock_uart.rx_buf[32..46],
+            [0xFF, 0xFF, 0xFD, 0x00, 0x02, 0x07, 0x00, 0x03, 0x64, 0x00, 0xBA, 0x01, 0x4D, 0x21]
+        );
+        assert_eq!(
+            mock_uart.rx_buf[46..62],
+            [
+                0xFF, 0xFF, 0xFD, 0x00, 0x02, 0x09, 0x00, 0x03, 0x70, 0x00, 0x83, 0x00, 0x00, 0x00,
+                0xE4, 0xF5
+            ]
+        );
+        assert_eq!(
+            mock_uart.rx_buf[62..78],
+            [
+                0xFF, 0xFF, 0xFD, 0x00, 0x02, 0x09, 0x00, 0x03, 0x6C, 0x00, 0x11, 0x00, 0x00, 0x00,
+                0xC3, 0xBD
+            ]
+        );
+        assert_eq!(
+            mock_uart.rx_buf[78..],
+            [0xFF, 0xFF, 0xFD, 0x00, 0x02, 0x07, 0x00, 0x03, 0x54, 0x00, 0x20, 0x03, 0x42, 0x3D]
+        );
+    }
+
+    #[test]
+    fn update_drive_mode() {
+        let mut mock_uart = MockSerial::new();
+        let mock_clock = MockClock::new();
+        let mut dxl = DynamixelControl::new(&mut mock_uart, &mock_clock, 115200);
+        let mode = dxl.get_drive_mode(1).unwrap();
+        assert_eq!(mode.is_time_based_profile(), true);
+        assert_eq!(mode.is_reverse(), false);
+        // Time-based Profile is kept
+        dxl.update_drive_mode(1, |m| m.reverse(true)).unwrap();
+        // Drive Mode is read before writing
+        assert_eq!(
+            mock_uart.rx_buf[14..28],
+            [0xFF, 0xFF, 0xFD, 0x00, 0x01, 0x07, 0x00, 0x02, 0x0A, 0x00, 0x01, 0x00, 0x21, 0xD3]
+        );
+        assert_eq!(
+            mock_uart.rx_buf[28..],
+            [0xFF, 0xFF, 0xFD, 0x00, 0x01, 0x06, 0x00, 0x03, 0x0A, 0x00, 0x05, 0x4B, 0x63]
+        );
+        assert_eq!(DriveMode::from_value(0x0F).slave(false).to_value(), 0x0D);
+        let mode = DriveMode::default().torque_on_by_goal_update(true);
+        assert_eq!(mode.to_value(), 0x08);
+    }
+
+    #[test]
+    fn echo_suppression() {
+        let mut mock_uart = MockSerial::new();
+        mock_uart.echo = true;
+        let mock_clock = MockClock::new();
+        let mut dxl = DynamixelControl::new(&mut mock_uart, &mock_clock, 115200);
+        dxl.set_echo_suppression(true);
+        let result = dxl.ping(1);
+        assert_eq!(result, Ok((0x0406, 0x26)));
+        let result = dxl.read_2byte(1, ControlTable::CurrentLimit);
+        assert_eq!(result, Ok(888));
+    }
+
+    #[test]
+    fn bus_collision() {
+        let mut mock_uart = MockSerial::new();
+        mock_uart.echo = true;
+        mock_uart.collision = true;
+        let mock_clock = MockClock::new();
+        let mut dxl = DynamixelControl::new(&mut mock_uart, &mock_clock, 115200);
+        dxl.set_echo_suppression(true);
+        let result = dxl.ping(1);
+        assert_eq!(result, Err(CommunicationResult::BusCollision));
+    }
+
+    #[test]
+    fn no_echo() {
+        let mut mock_uart = MockSerial::new();
+        let mock_clock = TickingClock::new();
+        let mut dxl = DynamixelControl::new(&mut mock_uart, &mock_clock, 115200);
+        dxl.set_echo_suppression(true);
+        // Nothing is read back without TX and RX tied together
+        let result = dxl.ping(5);
+        assert_eq!(result, Err(CommunicationResult::RxTimeout));
+    }
+
+    #[test]
+    fn p_series() {
+        let mut mock_uart = MockSerial::new();
+        let mock_clock = MockClock::new();
+        let mut dxl = DynamixelControl::new(&mut mock_uart, &mock_clock, 115200);
+        dxl.set_model(1, DynamixelModel::Ph54_200S500).unwrap();
+        assert_eq!(dxl.get_model(1), DynamixelModel::Ph54_200S500);
+        assert_eq!(dxl.get_model(2), DynamixelModel::Xc330T181);
+        // Quarter turn
+        let result = dxl.get_present_position(1).unwrap();
+        assert!((result - core::f32::consts::FRAC_PI_2).abs() < 0.001);
+        dxl.send_4byte_read_packet(1, ControlTable::GoalPosition)
+            .unwrap();
+        // Not in the control table of P series
+        let result = dxl.send_1byte_read_packet(1, ControlTable::PWMSlope);
+        assert_eq!(result, Err(CommunicationResult::NotAvailable));
+        assert_eq!(
+            mock_uart.rx_buf[..14],
+            [0xFF, 0xFF, 0xFD, 0x00, 0x01, 0x07, 0x00, 0x02, 0x44, 0x02, 0x04, 0x00, 0x14, 0x95]
+        );
+        assert_eq!(
+            mock_uart.rx_buf[14..],
+            [0xFF, 0xFF, 0xFD, 0x00, 0x01, 0x07, 0x00, 0x02, 0x34, 0x02, 0x04, 0x00, 0x03, 0xD5]
+        );
+    }
+
+    #[test]
+    fn get_hardware_error_status() {
+        let mut mock_uart = MockSerial::new();
+        let mock_clock = MockClock::new();
+        let mut dxl = DynamixelControl::new(&mut mock_uart, &mock_clock, 115200);
+        let status = dxl.get_hardware_error_status(1).unwrap();
+        assert_eq!(status.to_value(), 0x24);
+        assert_eq!(status.is_empty(), false);
+        assert_eq!(status.contains(HardwareError::Overheating), true);
+        assert_eq!(status.contains(HardwareError::MotorEncoder), false);
+    }
+
+    #[test]
+    fn status_alert() {
+        // ID1(XC330-T181) : Present Temperature(146, 0x0092, 1[byte]) = 80(0x50) with Alert
+        // ID1(XC330-T181) : Hardware Error Status(70, 0x0046, 1[byte]) = Overheating and Overload
+        let mut mock_uart = MockSerial::new();
+        let mock_clock = MockClock::new();
+        let mut dxl = DynamixelControl::new(&mut mock_uart, &mock_clock, 115200);
+        let result = dxl.read_1byte(1, ControlTable::PresentTemperature);
+        // Read Hardware Error Status automatically
+        assert_eq!(
+            mock_uart.rx_buf[14..],
+            [0xFF, 0xFF, 0xFD, 0x00, 0x01, 0x07, 0x00, 0x02, 0x46, 0x00, 0x01, 0x00, 0x3C, 0xA3]
+        );
+        match result {
+            Err(CommunicationResult::HardwareError { id, status }) => {
+                assert_eq!(id, 1);
+                assert_eq!(status.contains(HardwareError::Overheating), true);
+                assert_eq!(status.contains(HardwareError::Overload), true);
+                assert_eq!(status.contains(HardwareError::InputVoltage), false);
+            }
+            _ => assert!(false),
+        }
+    }
+
     #[test]
     #[ignore]
     fn read_after_write() {
@@ -514,13 +1522,71 @@ mod tests {
         assert_eq!(result, Ok(0x000000A6));
     }
 
+    #[test]
+    fn reg_write() {
+        // ID1(XM430-W210) : Register 512(0x00000200) to Goal Position(116, 0x0074, 4[byte])
+        let mut mock_uart = MockSerial::new();
+        let mock_clock = MockClock::new();
+        let mut dxl = DynamixelControl::new(&mut mock_uart, &mock_clock, 115200);
+        let data: u32 = 0x00000200;
+        let result = dxl.reg_write(1, ControlTable::GoalPosition, &data.to_le_bytes());
+        assert_eq!(
+            *mock_uart.rx_buf,
+            [
+                0xFF, 0xFF, 0xFD, 0x00, 0x01, 0x09, 0x00, 0x04, 0x74, 0x00, 0x00, 0x02, 0x00, 0x00,
+                0xB9, 0x0E
+            ]
+        );
+        assert_eq!(result.is_ok(), true);
+    }
+
+    #[test]
+    fn action() {
+        let mut mock_uart = MockSerial::new();
+        let mock_clock = MockClock::new();
+        let mut dxl = DynamixelControl::new(&mut mock_uart, &mock_clock, 115200);
+        let result = dxl.action(1);
+        assert_eq!(
+            *mock_uart.rx_buf,
+            [0xFF, 0xFF, 0xFD, 0x00, 0x01, 0x03, 0x00, 0x05, 0x02, 0xCE]
+        );
+        assert_eq!(result.is_ok(), true);
+    }
+
+    #[test]
+    fn broadcast_action() {
+        let mut mock_uart = MockSerial::new();
+        let mock_clock = MockClock::new();
+        let mut dxl = DynamixelControl::new(&mut mock_uart, &mock_clock, 115200);
+        let result = dxl.action(0xFE);
+        assert_eq!(
+            *mock_uart.rx_buf,
+            [0xFF, 0xFF, 0xFD, 0x00, 0xFE, 0x03, 0x00, 0x05, 0x2A, 0xC2]
+        );
+        assert_eq!(result.is_ok(), true);
+    }
+
+    #[test]
+    fn get_registered_instruction() {
+        // ID1(XM430-W210) : Registered Instruction(69, 0x0045, 1[byte]) = 1(0x01)
+        let mut mock_uart = MockSerial::new();
+        let mock_clock = MockClock::new();
+        let mut dxl = DynamixelControl::new(&mut mock_uart, &mock_clock, 115200);
+        let result = dxl.get_registered_instruction(1);
+        assert_eq!(
+            *mock_uart.rx_buf,
+            [0xFF, 0xFF, 0xFD, 0x00, 0x01, 0x07, 0x00, 0x02, 0x45, 0x00, 0x01, 0x00, 0x3C, 0x9F]
+        );
+        assert_eq!(result, Ok(true));
+    }
+
     #[test]
     fn factory_reset() {
         let mut mock_uart = MockSerial::new();
         let mock_clock = MockClock::new();
         let mut dxl = DynamixelControl::new(&mut mock_uart, &mock_clock, 115200);
 
-        let result = dxl.factory_reset(1);
+        let result = dxl.factory_reset(1, FactoryResetMode::ResetAllExceptIDAndBaudrate);
 
         assert_eq!(
             *mock_uart.rx_buf,
@@ -529,6 +1595,38 @@ mod tests {
         assert_eq!(result.is_ok(), true);
     }
 
+    #[test]
+    fn factory_reset_all() {
+        let mut mock_uart = MockSerial::new();
+        let mock_clock = MockClock::new();
+        let mut dxl = DynamixelControl::new(&mut mock_uart, &mock_clock, 115200);
+
+        let result = dxl.factory_reset(1, FactoryResetMode::ResetAll);
+
+        assert_eq!(
+            *mock_uart.rx_buf,
+            [0xFF, 0xFF, 0xFD, 0x00, 0x01, 0x04, 0x00, 0x06, 0xFF, 0xA6, 0x64]
+        );
+        assert_eq!(result.is_ok(), true);
+    }
+
+    #[test]
+    fn broadcast_factory_reset() {
+        let mut mock_uart = MockSerial::new();
+        let mock_clock = MockClock::new();
+        let mut dxl = DynamixelControl::new(&mut mock_uart, &mock_clock, 115200);
+
+        let result1 = dxl.factory_reset(0xFE, FactoryResetMode::ResetAll);
+        let result2 = dxl.factory_reset(0xFE, FactoryResetMode::ResetAllExceptID);
+
+        assert_eq!(
+            *mock_uart.rx_buf,
+            [0xFF, 0xFF, 0xFD, 0x00, 0xFE, 0x04, 0x00, 0x06, 0x01, 0x89, 0xCE]
+        );
+        assert_eq!(result1, Err(CommunicationResult::NotAvailable));
+        assert_eq!(result2.is_ok(), true);
+    }
+
     #[test]
     fn reboot() {
         let mut mock_uart = MockSerial::new();
@@ -544,6 +1642,83 @@ mod tests {
         assert_eq!(result.is_ok(), true);
     }
 
+    #[test]
+    fn clear_multi_turn() {
+        let mut mock_uart = MockSerial::new();
+        let mock_clock = MockClock::new();
+        let mut dxl = DynamixelControl::new(&mut mock_uart, &mock_clock, 115200);
+
+        let result = dxl.clear_multi_turn(1);
+
+        assert_eq!(
+            *mock_uart.rx_buf,
+            [
+                0xFF, 0xFF, 0xFD, 0x00, 0x01, 0x08, 0x00, 0x10, 0x01, 0x44, 0x58, 0x4C, 0x22, 0xB1,
+                0xDC
+            ]
+        );
+        assert_eq!(result.is_ok(), true);
+    }
+
+    #[test]
+    fn clear_error() {
+        let mut mock_uart = MockSerial::new();
+        let mock_clock = MockClock::new();
+        let mut dxl = DynamixelControl::new(&mut mock_uart, &mock_clock, 115200);
+
+        let result = dxl.clear_error(1);
+
+        assert_eq!(
+            *mock_uart.rx_buf,
+            [
+                0xFF, 0xFF, 0xFD, 0x00, 0x01, 0x08, 0x00, 0x10, 0x02, 0x45, 0x52, 0x43, 0x4C, 0xD5,
+                0xEB
+            ]
+        );
+        assert_eq!(result.is_ok(), true);
+    }
+
+    #[test]
+    fn backup_control_table() {
+        let mut mock_uart = MockSerial::new();
+        let mock_clock = MockClock::new();
+        let mut dxl = DynamixelControl::new(&mut mock_uart, &mock_clock, 115200);
+
+        let result = dxl.backup_control_table(1);
+
+        assert_eq!(
+            mock_uart.rx_buf[..15],
+            [
+                0xFF, 0xFF, 0xFD, 0x00, 0x01, 0x08, 0x00, 0x20, 0x01, 0x43, 0x54, 0x52, 0x4C, 0x16,
+                0xF5
+            ]
+        );
+        // Poll Backup Ready(147, 0x0093, 1[byte])
+        assert_eq!(
+            mock_uart.rx_buf[15..],
+            [0xFF, 0xFF, 0xFD, 0x00, 0x01, 0x07, 0x00, 0x02, 0x93, 0x00, 0x01, 0x00, 0x18, 0xA7]
+        );
+        assert_eq!(result.is_ok(), true);
+    }
+
+    #[test]
+    fn restore_control_table() {
+        let mut mock_uart = MockSerial::new();
+        let mock_clock = MockClock::new();
+        let mut dxl = DynamixelControl::new(&mut mock_uart, &mock_clock, 115200);
+
+        let result = dxl.restore_control_table(1);
+
+        assert_eq!(
+            mock_uart.rx_buf[..15],
+            [
+                0xFF, 0xFF, 0xFD, 0x00, 0x01, 0x08, 0x00, 0x20, 0x02, 0x43, 0x54, 0x52, 0x4C, 0x9E,
+                0xF5
+            ]
+        );
+        assert_eq!(result.is_ok(), true);
+    }
+
     #[test]
     fn sync_read_tx() {
         // ID1(XM430-W210) : Present Position(132, 0x0084, 4[byte]) = 166(0x000000A6)
@@ -572,6 +1747,104 @@ mod tests {
         assert_eq!(result2, Ok(0x0000081F));
     }
 
+    #[test]
+    fn fast_sync_read() {
+        // ID1(XM430-W210) : Present Position(132, 0x0084, 4[byte]) = 166(0x000000A6)
+        // ID2(XM430-W210) : Present Position(132, 0x0084, 4[byte]) = 2,079(0x0000081F)
+        let mut mock_uart = MockSerial::new();
+        let mock_clock = MockClock::new();
+        let mut dxl = DynamixelControl::new(&mut mock_uart, &mock_clock, 115200);
+        let result1 = dxl.fast_sync_read(
+            &[1, 2],
+            ControlTable::PresentPosition,
+            ControlTable::PresentPosition.to_size(),
+        );
+        // ID3 is not in the status packet.
+        let result2 = dxl.fast_sync_read(
+            &[1, 3],
+            ControlTable::PresentPosition,
+            ControlTable::PresentPosition.to_size(),
+        );
+        assert_eq!(
+            mock_uart.rx_buf[..16],
+            [
+                0xFF, 0xFF, 0xFD, 0x00, 0xFE, 0x09, 0x00, 0x8A, 0x84, 0x00, 0x04, 0x00, 0x01, 0x02,
+                0x4D, 0x72
+            ]
+        );
+        assert_eq!(result1.is_ok(), true);
+        let result1 = result1.unwrap();
+        assert_eq!(result1.len(), 2);
+        assert_eq!(result1[0].0, 1);
+        assert_eq!(*result1[0].1.as_ref().unwrap(), [0xA6, 0x00, 0x00, 0x00]);
+        assert_eq!(result1[1].0, 2);
+        assert_eq!(*result1[1].1.as_ref().unwrap(), [0x1F, 0x08, 0x00, 0x00]);
+
+        assert_eq!(result2.is_ok(), true);
+        let result2 = result2.unwrap();
+        assert_eq!(*result2[0].1.as_ref().unwrap(), [0xA6, 0x00, 0x00, 0x00]);
+        assert_eq!(result2[1].1, Err(CommunicationResult::RxCorrupt));
+    }
+
+    #[test]
+    fn fast_sync_read_crc_error() {
+        let mut mock_uart = MockSerial::new();
+        let mock_clock = MockClock::new();
+        let mut dxl = DynamixelControl::new(&mut mock_uart, &mock_clock, 115200);
+        let result = dxl.fast_sync_read(
+            &[1, 2],
+            ControlTable::PresentVelocity,
+            ControlTable::PresentVelocity.to_size(),
+        );
+        assert_eq!(
+            mock_uart.rx_buf[..16],
+            [
+                0xFF, 0xFF, 0xFD, 0x00, 0xFE, 0x09, 0x00, 0x8A, 0x80, 0x00, 0x04, 0x00, 0x01, 0x02,
+                0x4B, 0x92
+            ]
+        );
+        let result = result.unwrap();
+        assert_eq!(result[0].1, Err(CommunicationResult::RxCRCError));
+        assert_eq!(*result[1].1.as_ref().unwrap(), [0x1F, 0x08, 0x00, 0x00]);
+    }
+
+    #[test]
+    fn bulk_read() {
+        // ID1(XM430-W210) : Present Position(132, 0x0084, 4[byte]) = 166(0x000000A6)
+        // ID2(XC330-T181) : Current Limit(38, 0x0026, 2[byte]) = 888(0x0378)
+        // ID3 : No response
+        let mut mock_uart = MockSerial::new();
+        let mock_clock = TickingClock::new();
+        let mut dxl = DynamixelControl::new(&mut mock_uart, &mock_clock, 115200);
+        let result1 = dxl.bulk_read(&[
+            (2, ControlTable::CurrentLimit, 2),
+            (1, ControlTable::PresentPosition, 4),
+        ]);
+        let result2 = dxl.bulk_read(&[
+            (1, ControlTable::PresentPosition, 4),
+            (3, ControlTable::PresentPosition, 4),
+        ]);
+        assert_eq!(
+            mock_uart.rx_buf[..20],
+            [
+                0xFF, 0xFF, 0xFD, 0x00, 0xFE, 0x0D, 0x00, 0x92, 0x02, 0x26, 0x00, 0x02, 0x00, 0x01,
+                0x84, 0x00, 0x04, 0x00, 0xE1, 0x8C
+            ]
+        );
+        assert_eq!(result1.is_ok(), true);
+        let result1 = result1.unwrap();
+        assert_eq!(result1.len(), 2);
+        assert_eq!(result1[0].0, 2);
+        assert_eq!(*result1[0].1.as_ref().unwrap(), [0x78, 0x03]);
+        assert_eq!(result1[1].0, 1);
+        assert_eq!(*result1[1].1.as_ref().unwrap(), [0xA6, 0x00, 0x00, 0x00]);
+
+        assert_eq!(result2.is_ok(), true);
+        let result2 = result2.unwrap();
+        assert_eq!(*result2[0].1.as_ref().unwrap(), [0xA6, 0x00, 0x00, 0x00]);
+        assert_eq!(result2[1].1, Err(CommunicationResult::RxTimeout));
+    }
+
     #[test]
     fn sync_write_tx() {
         // ID1(XM430-W210) : Write 150(0x00000096) to Goal Position(116, 0x0074, 4[byte])
@@ -595,6 +1868,90 @@ mod tests {
         assert_eq!(result.is_ok(), true);
     }
 
+    #[test]
+    fn fast_bulk_read() {
+        // ID1(XM430-W210) : Present Position(132, 0x0084, 4[byte]) = 166(0x000000A6)
+        // ID2(XC330-T181) : Current Limit(38, 0x0026, 2[byte]) = 888(0x0378)
+        let mut mock_uart = MockSerial::new();
+        let mock_clock = MockClock::new();
+        let mut dxl = DynamixelControl::new(&mut mock_uart, &mock_clock, 115200);
+        let result = dxl.fast_bulk_read(&[
+            (1, ControlTable::PresentPosition, 4),
+            (2, ControlTable::CurrentLimit, 2),
+        ]);
+        assert_eq!(
+            *mock_uart.rx_buf,
+            [
+                0xFF, 0xFF, 0xFD, 0x00, 0xFE, 0x0D, 0x00, 0x9A, 0x01, 0x84, 0x00, 0x04, 0x00, 0x02,
+                0x26, 0x00, 0x02, 0x00, 0xCF, 0xCC
+            ]
+        );
+        assert_eq!(result.is_ok(), true);
+        let result = result.unwrap();
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[0].0, 1);
+        assert_eq!(*result[0].1.as_ref().unwrap(), [0xA6, 0x00, 0x00, 0x00]);
+        assert_eq!(result[1].0, 2);
+        assert_eq!(*result[1].1.as_ref().unwrap(), [0x78, 0x03]);
+    }
+
+    #[test]
+    fn fast_bulk_read_crc_error() {
+        let mut mock_uart = MockSerial::new();
+        let mock_clock = MockClock::new();
+        let mut dxl = DynamixelControl::new(&mut mock_uart, &mock_clock, 115200);
+        let result = dxl.fast_bulk_read(&[
+            (1, ControlTable::PresentVelocity, 4),
+            (2, ControlTable::CurrentLimit, 2),
+        ]);
+        let result = result.unwrap();
+        assert_eq!(result[0].1, Err(CommunicationResult::RxCRCError));
+        assert_eq!(*result[1].1.as_ref().unwrap(), [0x78, 0x03]);
+    }
+
+    #[test]
+    fn bulk_write_tx() {
+        // ID1(XC330-T181) : Write 100(0x0064) to Goal Current(102, 0x0066, 2[byte])
+        // ID2(XM430-W210) : Write 512(0x00000200) to Goal Position(116, 0x0074, 4[byte])
+        let mut mock_uart = MockSerial::new();
+        let mock_clock = MockClock::new();
+        let mut dxl = DynamixelControl::new(&mut mock_uart, &mock_clock, 115200);
+        let result = dxl.send_bulk_write_packet(&[
+            (1, ControlTable::GoalCurrent, &(100 as u16).to_le_bytes()),
+            (2, ControlTable::GoalPosition, &(512 as u32).to_le_bytes()),
+        ]);
+        assert_eq!(
+            *mock_uart.rx_buf,
+            [
+                0xFF, 0xFF, 0xFD, 0x00, 0xFE, 0x13, 0x00, 0x93, 0x01, 0x66, 0x00, 0x02, 0x00, 0x64,
+                0x00, 0x02, 0x74, 0x00, 0x04, 0x00, 0x00, 0x02, 0x00, 0x00, 0x4F, 0x82
+            ]
+        );
+        assert_eq!(result.is_ok(), true);
+    }
+
+    #[test]
+    fn bulk_write_invalid() {
+        let mut mock_uart = MockSerial::new();
+        let mock_clock = MockClock::new();
+        let mut dxl = DynamixelControl::new(&mut mock_uart, &mock_clock, 115200);
+        // Size of Goal Position is 4[byte]
+        let result = dxl.send_bulk_write_packet(&[(1, ControlTable::GoalPosition, &[0x00, 0x02])]);
+        assert_eq!(result, Err(CommunicationResult::NotAvailable));
+
+        // 27 IDs fill 253[byte], and FF FF FD in each data needs stuffing.
+        let data = 0xFDFFFF00_u32.to_le_bytes();
+        let mut params = Vec::<(u8, ControlTable, &[u8]), 27>::new();
+        for id in 1..=27 {
+            params
+                .push((id, ControlTable::GoalPosition, &data))
+                .unwrap();
+        }
+        let result = dxl.send_bulk_write_packet(&params);
+        assert_eq!(result, Err(CommunicationResult::NotAvailable));
+        assert!(mock_uart.rx_buf.is_empty());
+    }
+
     #[test]
     fn u16_to_u8() {
         assert_eq!((0xFBFA as u16).to_le_bytes(), [0xFA, 0xFB]);
@@ -1,5 +1,14 @@
+use crate::protocol1::build_protocol1_packet;
+use crate::protocol1::find_protocol1_status_packet;
+use crate::protocol1::parse_protocol1_status_packet;
+use crate::protocol1::Protocol1Packet;
+use crate::registry::check_limits;
+use crate::registry::DeviceRegistry;
+use crate::Access;
 use crate::ControlTable;
 use crate::DynamixelControl;
+use crate::FactoryResetMode;
+use crate::HardwareErrorStatus;
 use crate::Instruction;
 use core::fmt;
 use core::result::Result;
@@ -8,6 +17,13 @@ use heapless::Vec;
 
 pub const MAX_PACKET_LEN: usize = 256;
 pub const BROADCAST_ID: u8 = 0xFE;
+pub const MAX_ID: u8 = 0xFC;
+pub const MAX_DEVICE_NUM: usize = MAX_ID as usize + 1;
+pub const MAX_READ_ID_NUM: usize = 32;
+
+/// Data read from each device by an instruction addressed to multiple devices.
+pub type MultiReadResult =
+    Vec<(u8, Result<Vec<u8, MAX_PACKET_LEN>, CommunicationResult>), MAX_READ_ID_NUM>;
 
 #[allow(dead_code)]
 pub enum Packet {
@@ -41,8 +57,15 @@ impl Packet {
     }
 }
 
+/// Version of DYNAMIXEL Protocol used to communicate with the devices.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProtocolVersion {
+    V1,
+    V2,
+}
+
 #[allow(dead_code)]
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 #[repr(u8)]
 pub enum ErrorBit {
     ErrNone = 0x00,
@@ -63,8 +86,28 @@ impl From<ErrorBit> for u8 {
     }
 }
 
+impl TryFrom<u8> for ErrorBit {
+    type Error = u8;
+
+    /// Error number of the status packet, without the alert bit.
+    /// Err(value) for the numbers not defined by the protocol.
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0x00 => Ok(ErrorBit::ErrNone),
+            0x01 => Ok(ErrorBit::ErrResultFail),
+            0x02 => Ok(ErrorBit::ErrInstruction),
+            0x03 => Ok(ErrorBit::ErrCRC),
+            0x04 => Ok(ErrorBit::ErrDataRange),
+            0x05 => Ok(ErrorBit::ErrDataLength),
+            0x06 => Ok(ErrorBit::ErrDataLimit),
+            0x07 => Ok(ErrorBit::ErrAccess),
+            v => Err(v),
+        }
+    }
+}
+
 #[allow(dead_code)]
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum CommunicationResult {
     Success,
     PortBusy,
@@ -76,9 +119,43 @@ pub enum CommunicationResult {
     RxCorrupt,
     RxCRCError,
     NotAvailable,
+    /// The device reported an error in the status packet.
+    /// error: Err(error number) when the number is not defined by the protocol.
+    /// alert: Hardware error occurred on the device.
+    StatusError {
+        error: Result<ErrorBit, u8>,
+        alert: bool,
+    },
+    /// The device reported alert and this is its Hardware Error Status.
+    HardwareError {
+        id: u8,
+        status: HardwareErrorStatus,
+    },
+    /// The echo of the instruction packet differs from the transmitted one.
+    BusCollision,
+    /// The item is read only.
+    ReadOnly,
+    /// The value is out of the range of the item or the limit of the device.
+    OutOfRange,
+    /// The item is in EEPROM area and Torque Enable of the device is on.
+    TorqueEnabled,
     SomethingWentWrong,
 }
 
+impl CommunicationResult {
+    /// Check the error byte of the status packet.
+    pub fn from_status_error(error: u8) -> Result<(), CommunicationResult> {
+        if error == 0x00 {
+            Ok(())
+        } else {
+            Err(CommunicationResult::StatusError {
+                error: ErrorBit::try_from(error & 0x7F),
+                alert: error & 0x80 != 0,
+            })
+        }
+    }
+}
+
 impl fmt::Display for CommunicationResult {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match *self {
@@ -100,6 +177,41 @@ impl fmt::Display for CommunicationResult {
             CommunicationResult::NotAvailable => {
                 write!(f, "[TxRxResult] Protocol does not support This function!")
             }
+            CommunicationResult::StatusError {
+                error: Ok(error),
+                alert,
+            } => {
+                write!(
+                    f,
+                    "[TxRxResult] Status packet has error {:?} (alert: {})!",
+                    error, alert
+                )
+            }
+            CommunicationResult::StatusError {
+                error: Err(error),
+                alert,
+            } => {
+                write!(
+                    f,
+                    "[TxRxResult] Status packet has unknown error {} (alert: {})!",
+                    error, alert
+                )
+            }
+            CommunicationResult::HardwareError { id, status } => {
+                write!(
+                    f,
+                    "[TxRxResult] Hardware error {:?} occurred on ID {}!",
+                    status, id
+                )
+            }
+            CommunicationResult::BusCollision => {
+                write!(f, "[TxRxResult] Echo of instruction packet is broken!")
+            }
+            CommunicationResult::ReadOnly => write!(f, "[TxRxResult] Item is read only!"),
+            CommunicationResult::OutOfRange => write!(f, "[TxRxResult] Value is out of range!"),
+            CommunicationResult::TorqueEnabled => {
+                write!(f, "[TxRxResult] EEPROM is locked while torque is enabled!")
+            }
             CommunicationResult::SomethingWentWrong => {
                 write!(f, "[TxRxResult] Something went wrong!")
             }
@@ -113,95 +225,23 @@ impl<'a> DynamixelControl<'a> {
         [0x00; 4] // Header and reserved len
     }
 
-    fn add_stuffing(&mut self, msg: &mut Vec<u8, MAX_PACKET_LEN>) {
-        let packet_length_in =
-            u16::from_le_bytes([msg[Packet::LengthL.to_pos()], msg[Packet::LengthH.to_pos()]]);
-        let mut packet_length_out = packet_length_in;
-
-        if packet_length_in < 8 {
-            // INSTRUCTION, ADDR_L, ADDR_H, CRC16_L, CRC16_H + FF FF FD
-            return;
-        }
-
-        let packet_length_before_crc = packet_length_in - 2;
-        for i in 3..packet_length_before_crc as usize {
-            let check = i + Packet::Instruction.to_pos() - 2;
-            if msg[check + 0] == 0xFF && msg[check + 1] == 0xFF && msg[check + 2] == 0xFD {
-                packet_length_out += 1;
-            }
-        }
-
-        if packet_length_in == packet_length_out {
-            // no stuffing required
-            return;
-        }
-        msg.resize(
-            msg.len() + packet_length_out as usize - packet_length_in as usize,
-            0,
-        )
-        .unwrap();
-
-        let mut out_index = packet_length_out as usize + 6 - 2; // last index before crc
-        let mut in_index = packet_length_in as usize + 6 - 2; // last index before crc
-        while out_index != in_index {
-            if msg[in_index] == 0xFD && msg[in_index - 1] == 0xFF && msg[in_index - 2] == 0xFF {
-                msg[out_index] = 0xFD; // byte stuffing
-                out_index -= 1;
-                if out_index != in_index {
-                    msg[out_index] = msg[in_index]; // FD
-                    out_index -= 1;
-                    in_index -= 1;
-                    msg[out_index] = msg[in_index]; // FF
-                    out_index -= 1;
-                    in_index -= 1;
-                    msg[out_index] = msg[in_index]; // FF
-                    out_index -= 1;
-                    in_index -= 1;
-                }
-            } else {
-                msg[out_index] = msg[in_index];
-                out_index -= 1;
-                in_index -= 1;
-            }
-        }
-
-        msg[Packet::LengthL.to_pos()] = packet_length_out.to_le_bytes()[0];
-        msg[Packet::LengthH.to_pos()] = packet_length_out.to_le_bytes()[1];
-
-        return;
-    }
-
-    fn remove_stuffing(&mut self, msg: &mut Vec<u8, MAX_PACKET_LEN>) {
-        let packet_length_in =
-            u16::from_le_bytes([msg[Packet::LengthL.to_pos()], msg[Packet::LengthH.to_pos()]]);
-        let mut packet_length_out = packet_length_in;
-
-        let mut index = Packet::Instruction.to_pos() as usize;
-        let mut i = 0;
-        // except CRC
-        while i < (packet_length_in - 2) as usize {
-            if msg[i + Packet::Instruction.to_pos()] == 0xFD
-                && msg[i + Packet::Instruction.to_pos() + 1] == 0xFD
-                && msg[i + Packet::Instruction.to_pos() - 1] == 0xFF
-                && msg[i + Packet::Instruction.to_pos() - 2] == 0xFF
-            {
-                // FF FF FD FD
-                packet_length_out -= 1;
-                i += 1;
-            }
-            msg[index] = msg[i + Packet::Instruction.to_pos()];
-            index += 1;
-            i += 1;
-        }
-
-        msg[index] = msg[Packet::Instruction.to_pos() + packet_length_in as usize - 2];
-        index += 1;
-        msg[index] = msg[Packet::Instruction.to_pos() + packet_length_in as usize - 1];
-        index += 1;
-
-        msg[Packet::LengthL.to_pos()] = packet_length_out.to_le_bytes()[0];
-        msg[Packet::LengthH.to_pos()] = packet_length_out.to_le_bytes()[1];
-        msg.resize(index, 0).unwrap();
+    /// Validate the value enabled by set_range_check.
+    /// The limits of the device are read when they are not cached.
+    fn check_range(
+        &mut self,
+        id: u8,
+        data_name: ControlTable,
+        data: &[u8],
+    ) -> Result<(), CommunicationResult> {
+        let value = match self.registry.check_range(id, data_name, data)? {
+            Some(v) => v,
+            None => return Ok(()),
+        };
+        let limits = match self.get_limits(id) {
+            Some(v) => v,
+            None => self.read_limits(id)?,
+        };
+        check_limits(data_name, value, &limits)
     }
 
     /// Set packet without crc.
@@ -209,148 +249,100 @@ impl<'a> DynamixelControl<'a> {
         &mut self,
         mut msg: Vec<u8, MAX_PACKET_LEN>,
     ) -> Result<(), CommunicationResult> {
-        self.add_stuffing(&mut msg);
-
-        // make header
-        msg[Packet::Header0.to_pos()] = 0xFF;
-        msg[Packet::Header1.to_pos()] = 0xFF;
-        msg[Packet::Header2.to_pos()] = 0xFD;
-        msg[Packet::Reserved.to_pos()] = 0x00;
-
-        // add crc
-        msg.extend(self.calc_crc_value(&msg).to_le_bytes().iter().cloned());
+        finish_packet(&mut msg)?;
+        self.transmit_packet(&msg, 0)
+    }
 
+    /// Send the finished packet and wait for status_len bytes of the status packets.
+    pub(crate) fn transmit_packet(
+        &mut self,
+        msg: &[u8],
+        status_len: usize,
+    ) -> Result<(), CommunicationResult> {
         self.clear_port();
-        self.uart.write_bytes(&msg);
+        self.uart.write_bytes(msg);
         // for m in msg {
         //     self.uart.write_byte(m);
         // }
 
+        self.receive_echo(msg)?;
+        self.set_packet_timeout_length(msg.len() + status_len);
+        Ok(())
+    }
+
+    /// Read back the transmitted bytes enabled by set_echo_suppression.
+    fn receive_echo(&mut self, msg: &[u8]) -> Result<(), CommunicationResult> {
+        if !self.echo_suppression {
+            return Ok(());
+        }
+        self.set_packet_timeout_length(msg.len());
+        let mut echo = Vec::<u8, MAX_PACKET_LEN>::new();
+        let mut res = Vec::<u8, MAX_PACKET_LEN>::new();
+
+        while echo.len() < msg.len() {
+            res.resize(msg.len() - echo.len(), 0).unwrap();
+            match self.uart.read_bytes(&mut res) {
+                None => {}
+                Some(readlen) => {
+                    echo.extend(res[0..readlen].iter().cloned());
+                }
+            }
+            check_echo(msg, &echo)?;
+            if echo.len() < msg.len() && self.is_packet_timeout() {
+                return Err(CommunicationResult::RxTimeout);
+            }
+        }
         Ok(())
     }
 
-    fn receive_packet(&mut self) -> Result<Vec<u8, MAX_PACKET_LEN>, CommunicationResult> {
+    fn receive_packet(
+        &mut self,
+        version: ProtocolVersion,
+    ) -> Result<Vec<u8, MAX_PACKET_LEN>, CommunicationResult> {
         let result;
-        let mut wait_length = 11; // minimum length (HEADER0 HEADER1 HEADER2 RESERVED ID LENGTH_L LENGTH_H INST ERROR CRC16_L CRC16_H)
+        let mut wait_length = status_packet_len(version, 0); // minimum length
         let mut msg = Vec::<u8, MAX_PACKET_LEN>::new(); // VecDeque is not implemented in heapless.
         let mut res = Vec::<u8, MAX_PACKET_LEN>::new();
 
         loop {
             res.resize(wait_length - msg.len(), 0).unwrap();
-            match self.uart.read_bytes(&mut *res) {
+            match self.uart.read_bytes(&mut res) {
                 None => {}
                 Some(readlen) => {
                     msg.extend(res[0..readlen].iter().cloned());
                 }
             }
 
-            // while msg.len() < wait_length {
-            //     match self.uart.read_byte() {
-            //         None => {
-            //             break;
-            //         }
-            //         Some(res) => {
-            //             msg.push(res).unwrap();
-            //         }
-            //     }
-            // }
-
-            if msg.len() >= wait_length {
-                let mut idx = 0;
-                // find packet header
-                while idx < (msg.len() - 3) {
-                    if msg[idx + Packet::Header0.to_pos()] == 0xFF
-                        && msg[idx + Packet::Header1.to_pos()] == 0xFF
-                        && msg[idx + Packet::Header2.to_pos()] == 0xFD
-                        && msg[idx + Packet::Reserved.to_pos()] == 0x00
-                    {
-                        break;
-                    }
-                    idx += 1;
-                }
-
-                if idx == 0 {
-                    // found at the beginning of the packet
-                    if msg[Packet::Reserved.to_pos()] != 0x00
-                        || msg[Packet::Id.to_pos()] > 0xFC
-                        || u16::from_le_bytes([
-                            msg[Packet::LengthL.to_pos()],
-                            msg[Packet::LengthH.to_pos()],
-                        ]) as usize
-                            > MAX_PACKET_LEN
-                        || msg[Packet::Instruction.to_pos()] != 0x55
-                    {
-                        // remove the first byte in the packet
-                        for s in 0..msg.len() - 1 {
-                            msg[s] = msg[s + 1];
-                        }
-                        msg.truncate(msg.len() - 1);
-                        continue;
-                    }
-                    // re-calculate the exact length of the rx packet
-                    if wait_length
-                        != u16::from_le_bytes([
-                            msg[Packet::LengthL.to_pos()],
-                            msg[Packet::LengthH.to_pos()],
-                        ]) as usize
-                            + Packet::LengthH.to_pos()
-                            + 1
-                    {
-                        wait_length = u16::from_le_bytes([
-                            msg[Packet::LengthL.to_pos()],
-                            msg[Packet::LengthH.to_pos()],
-                        ]) as usize
-                            + Packet::LengthH.to_pos()
-                            + 1;
-                        continue;
-                    }
-
-                    if msg.len() < wait_length {
-                        // check timeout
-                        if self.is_packet_timeout() == true {
-                            if msg.len() == 0 {
-                                result = CommunicationResult::RxTimeout;
-                            } else {
-                                result = CommunicationResult::RxCorrupt;
-                            }
-                            break;
-                        } else {
-                            continue;
-                        }
-                    }
-
-                    // verify CRC16
-                    let crc = u16::from_le_bytes([msg[msg.len() - 2], msg[msg.len() - 1]]);
-                    if self.calc_crc_value(&msg[..msg.len() - 2]) == crc {
-                        result = CommunicationResult::Success;
-                    } else {
-                        result = CommunicationResult::RxCRCError;
-                    }
+            let found = match version {
+                ProtocolVersion::V1 => find_protocol1_status_packet(&mut msg, &mut wait_length),
+                ProtocolVersion::V2 => find_status_packet(&mut msg, &mut wait_length),
+            };
+            match found {
+                Ok(true) => {
+                    result = CommunicationResult::Success;
                     break;
-                } else {
-                    // remove unnecessary packets
-                    for s in 0..(msg.len() - idx) {
-                        msg[s] = msg[idx + s];
-                    }
-                    msg.truncate(msg.len() - idx);
                 }
-            } else {
-                // check timeout
-                if self.is_packet_timeout() == true {
-                    if msg.len() == 0 {
-                        result = CommunicationResult::RxTimeout;
-                    } else {
-                        result = CommunicationResult::RxCorrupt;
-                    }
+                Ok(false) => {}
+                Err(e) => {
+                    result = e;
                     break;
                 }
             }
+            // check timeout
+            if self.is_packet_timeout() {
+                if msg.is_empty() {
+                    result = CommunicationResult::RxTimeout;
+                } else {
+                    result = CommunicationResult::RxCorrupt;
+                }
+                break;
+            }
             // usleep(0);
         }
         self.is_using = false;
 
-        if result == CommunicationResult::Success {
-            self.remove_stuffing(&mut msg);
+        if result == CommunicationResult::Success && version == ProtocolVersion::V2 {
+            remove_stuffing(&mut msg);
         }
 
         if result == CommunicationResult::Success {
@@ -360,131 +352,159 @@ impl<'a> DynamixelControl<'a> {
         }
     }
 
-    /// 👺Broadcast is not implemented yet.
-    pub fn ping(&mut self, id: u8) -> Result<(u16, u8), CommunicationResult> {
-        let length: u16 = 1 + 2; // instruction + crc
-        let mut msg = Vec::<u8, MAX_PACKET_LEN>::new();
-
-        msg.extend(self.reserve_msg_header().iter().cloned());
-        msg.push(id).unwrap();
-        msg.extend(length.to_le_bytes().iter().cloned()); // Set length temporary
-        msg.push(Instruction::Ping as u8).unwrap();
-        let packet_len = msg.len() + 2;
-        match self.send_packet(msg) {
-            Ok(_) => {
-                self.set_packet_timeout_length(packet_len);
-            }
-            Err(e) => return Err(e),
+    /// Receive the status packet from the device and return its parameters.
+    /// When the device reports alert, its Hardware Error Status is read.
+    fn receive_status_packet(
+        &mut self,
+        id: u8,
+        data_length: u16,
+    ) -> Result<Vec<u8, MAX_PACKET_LEN>, CommunicationResult> {
+        match self.receive_checked_status_packet(id, data_length, false) {
+            Err(e) => Err(self.diagnose_alert(id, e)),
+            Ok(v) => Ok(v),
         }
+    }
 
-        let status;
-        match self.receive_packet() {
-            Ok(v) => status = v,
-            Err(e) => return Err(e),
-        }
+    fn receive_checked_status_packet(
+        &mut self,
+        id: u8,
+        data_length: u16,
+        ignore_alert: bool,
+    ) -> Result<Vec<u8, MAX_PACKET_LEN>, CommunicationResult> {
+        let version = self.get_id_protocol_version(id);
+        let status = self.receive_packet(version)?;
+        parse_status_packet(version, &status, id, data_length, ignore_alert)
+    }
 
-        // header + id + length + instruction + err + param + crc
-        // id check
-        if status[Packet::Id.to_pos()] != id {
-            return Err(CommunicationResult::SomethingWentWrong);
-        }
-        // data length check
-        if u16::from_le_bytes([
-            status[Packet::LengthL.to_pos()],
-            status[Packet::LengthH.to_pos()],
-        ]) - 4
-            != 3
-        {
-            return Err(CommunicationResult::SomethingWentWrong);
-        }
-        // instruction check
-        if status[Packet::Instruction.to_pos()] != Instruction::Status as u8 {
-            return Err(CommunicationResult::SomethingWentWrong);
-        }
-        if status[Packet::Error.to_pos()] != 0x00 {
-            return Err(CommunicationResult::SomethingWentWrong);
+    /// Replace the status error with alert by the Hardware Error Status of the device.
+    fn diagnose_alert(&mut self, id: u8, error: CommunicationResult) -> CommunicationResult {
+        match error {
+            CommunicationResult::StatusError { alert: true, .. } => {
+                match self.get_hardware_error_status(id) {
+                    Ok(status) => CommunicationResult::HardwareError { id, status },
+                    Err(_) => error,
+                }
+            }
+            _ => error,
         }
+    }
 
-        let model_number = u16::from_le_bytes([
-            status[Packet::Error.to_pos() + 1],
-            status[Packet::Error.to_pos() + 2],
-        ]);
-        let firmware_version = status[Packet::Error.to_pos() + 3];
-        Ok((model_number, firmware_version))
+    fn diagnose_multi_read_alert(&mut self, result: &mut MultiReadResult) {
+        for (id, data) in result.iter_mut() {
+            if let Err(e) = data {
+                *e = self.diagnose_alert(*id, *e);
+            }
+        }
     }
 
-    fn send_read_packet(
+    /// Status of the device always has alert while hardware error exists.
+    pub fn get_hardware_error_status(
         &mut self,
         id: u8,
-        data_name: ControlTable,
-        data_size: u16,
-    ) -> Result<(), CommunicationResult> {
-        if id >= BROADCAST_ID {
+    ) -> Result<HardwareErrorStatus, CommunicationResult> {
+        if self.get_id_protocol_version(id) == ProtocolVersion::V1 {
             return Err(CommunicationResult::NotAvailable);
         }
-
-        let address = data_name.to_address();
-        let length: u16 = 1 + 2 + 2 + 2; // instruction + adress + data length + crc
-        let mut msg = Vec::<u8, MAX_PACKET_LEN>::new();
-
-        msg.extend(self.reserve_msg_header().iter().cloned());
-        msg.push(id).unwrap();
-        msg.extend(length.to_le_bytes().iter().cloned()); // Set length temporary
-        msg.push(Instruction::Read as u8).unwrap();
-        msg.extend(address.to_le_bytes().iter().cloned());
-        msg.extend(data_size.to_le_bytes().iter().cloned());
-        let packet_len = msg.len() + 2;
-        match self.send_packet(msg) {
-            Ok(_) => {
-                self.set_packet_timeout_length(packet_len);
-                Ok(())
-            }
+        match self.send_read_packet(id, ControlTable::HardwareErrorStatus, 1) {
+            Ok(_) => {}
+            Err(e) => return Err(e),
+        }
+        match self.receive_checked_status_packet(id, 1, true) {
+            Ok(v) => Ok(HardwareErrorStatus::from_value(v[0])),
             Err(e) => Err(e),
         }
     }
 
-    fn receive_read_packet(
-        &mut self,
-        id: u8,
-        data_length: u16,
-    ) -> Result<Vec<u8, MAX_PACKET_LEN>, CommunicationResult> {
-        let status;
-        match self.receive_packet() {
-            Ok(v) => {
-                status = v;
-            }
+    /// Register the model of the device when it is known.
+    /// Use broadcast_ping for BROADCAST_ID.
+    pub fn ping(&mut self, id: u8) -> Result<(u16, u8), CommunicationResult> {
+        let msg = self.registry.ping_packet(id)?;
+        match self.transmit_packet(&msg, 0) {
+            Ok(_) => {}
             Err(e) => return Err(e),
         }
 
-        // header + id + length + instruction + err + param + crc
+        let data = match self.get_id_protocol_version(id) {
+            // Protocol 1.0 ping has no model number, so it is read after ping.
+            ProtocolVersion::V1 => {
+                self.receive_status_packet(id, 0)?;
+                self.read(id, ControlTable::ModelNumber, 3)?
+            }
+            ProtocolVersion::V2 => self.receive_status_packet(id, 3)?,
+        };
+
+        // Model Number(2[byte]) + Firmware Version(1[byte])
+        let model_number = u16::from_le_bytes([data[0], data[1]]);
+        self.registry.register_model(id, model_number);
+        Ok((model_number, data[2]))
+    }
 
-        // id check
-        if status[Packet::Id.to_pos()] != id {
-            return Err(CommunicationResult::SomethingWentWrong);
+    /// Ping all devices on the bus and register their models.
+    /// Returns (id, model number, firmware version) of every device that answered.
+    pub fn broadcast_ping(
+        &mut self,
+    ) -> Result<Vec<(u8, u16, u8), MAX_DEVICE_NUM>, CommunicationResult> {
+        if self.get_protocol_version() == ProtocolVersion::V1 {
+            return Err(CommunicationResult::NotAvailable);
+        }
+        const STATUS_PACKET_LEN: u64 = 14;
+        let msg = build_packet(ProtocolVersion::V2, BROADCAST_ID, Instruction::Ping, &[])?;
+        match self.transmit_packet(&msg, 0) {
+            Ok(_) => {
+                // Devices answer one after another in order of ID, so wait for all of them.
+                let timeout_usec =
+                    self.tx_time_per_byte * STATUS_PACKET_LEN * MAX_DEVICE_NUM as u64
+                        + 3_000 * MAX_ID as u64
+                        + 16_000;
+                self.set_packet_timeout_micros(timeout_usec);
+            }
+            Err(e) => return Err(e),
         }
 
-        // data length check
-        if u16::from_le_bytes([
-            status[Packet::LengthL.to_pos()],
-            status[Packet::LengthH.to_pos()],
-        ]) - 4
-            != data_length
-        {
-            return Err(CommunicationResult::SomethingWentWrong);
+        let mut devices = Vec::<(u8, u16, u8), MAX_DEVICE_NUM>::new();
+        loop {
+            let status = match self.receive_packet(ProtocolVersion::V2) {
+                Ok(v) => v,
+                Err(CommunicationResult::RxCRCError) => continue,
+                Err(_) => break,
+            };
+
+            // header + id + length + instruction + err + param + crc
+            // data length check
+            let length = u16::from_le_bytes([
+                status[Packet::LengthL.to_pos()],
+                status[Packet::LengthH.to_pos()],
+            ]);
+            if length.checked_sub(4) != Some(3) {
+                continue;
+            }
+            // Devices with hardware error are still on the bus, so error is not checked.
+            let model_number = u16::from_le_bytes([
+                status[Packet::Error.to_pos() + 1],
+                status[Packet::Error.to_pos() + 2],
+            ]);
+            let firmware_version = status[Packet::Error.to_pos() + 3];
+            self.registry
+                .register_model(status[Packet::Id.to_pos()], model_number);
+            if devices
+                .push((status[Packet::Id.to_pos()], model_number, firmware_version))
+                .is_err()
+            {
+                break;
+            }
         }
 
-        let mut data = Vec::<u8, MAX_PACKET_LEN>::new();
-        // for i in 0..data_length as usize {
-        //     data.push(status[Packet::Error.to_pos() + 1 + i]).unwrap();
-        // }
-        data.extend(
-            status
-                [(Packet::Error.to_pos() + 1)..(Packet::Error.to_pos() + 1 + data_length as usize)]
-                .iter()
-                .cloned(),
-        );
+        Ok(devices)
+    }
 
-        Ok(data)
+    fn send_read_packet(
+        &mut self,
+        id: u8,
+        data_name: ControlTable,
+        data_size: u16,
+    ) -> Result<(), CommunicationResult> {
+        let msg = self.registry.read_packet(id, data_name, data_size)?;
+        self.transmit_packet(&msg, 0)
     }
 
     /// TxRx
@@ -498,7 +518,37 @@ impl<'a> DynamixelControl<'a> {
             Ok(_) => {}
             Err(e) => return Err(e),
         }
-        self.receive_read_packet(id, data_length)
+        self.receive_status_packet(id, data_length)
+    }
+
+    /// The status packet of Protocol 1.0 can not be widened without the item,
+    /// so the size must be the same as Protocol 1.0 control table.
+    fn send_sized_read_packet(
+        &mut self,
+        id: u8,
+        data_name: ControlTable,
+        data_size: u16,
+    ) -> Result<(), CommunicationResult> {
+        if self.get_id_protocol_version(id) == ProtocolVersion::V1
+            && data_name.to_protocol1_size() != Some(data_size)
+        {
+            return Err(CommunicationResult::NotAvailable);
+        }
+        self.send_read_packet(id, data_name, data_size)
+    }
+
+    /// Read the item as the size of Protocol 2.0 control table.
+    /// Smaller Protocol 1.0 items are zero extended.
+    fn read_sized(
+        &mut self,
+        id: u8,
+        data_name: ControlTable,
+        data_size: u16,
+    ) -> Result<Vec<u8, MAX_PACKET_LEN>, CommunicationResult> {
+        let size = self.registry.get_read_size(id, data_name, data_size)?;
+        let mut data = self.read(id, data_name, size)?;
+        data.resize(data_size as usize, 0x00).unwrap();
+        Ok(data)
     }
 
     pub fn send_1byte_read_packet(
@@ -506,10 +556,10 @@ impl<'a> DynamixelControl<'a> {
         id: u8,
         data_name: ControlTable,
     ) -> Result<(), CommunicationResult> {
-        self.send_read_packet(id, data_name, 1)
+        self.send_sized_read_packet(id, data_name, 1)
     }
     pub fn receive_1byte_read_packet(&mut self, id: u8) -> Result<u8, CommunicationResult> {
-        match self.receive_read_packet(id, 1) {
+        match self.receive_status_packet(id, 1) {
             Ok(v) => Ok(u8::from_le_bytes([v[0]])),
             Err(e) => Err(e),
         }
@@ -519,7 +569,7 @@ impl<'a> DynamixelControl<'a> {
         id: u8,
         data_name: ControlTable,
     ) -> Result<u8, CommunicationResult> {
-        match self.read(id, data_name, 1) {
+        match self.read_sized(id, data_name, 1) {
             Ok(v) => Ok(u8::from_le_bytes([v[0]])),
             Err(e) => Err(e),
         }
@@ -529,10 +579,10 @@ impl<'a> DynamixelControl<'a> {
         id: u8,
         data_name: ControlTable,
     ) -> Result<(), CommunicationResult> {
-        self.send_read_packet(id, data_name, 2)
+        self.send_sized_read_packet(id, data_name, 2)
     }
     pub fn receive_2byte_read_packet(&mut self, id: u8) -> Result<u16, CommunicationResult> {
-        match self.receive_read_packet(id, 2) {
+        match self.receive_status_packet(id, 2) {
             Ok(v) => Ok(u16::from_le_bytes([v[0], v[1]])),
             Err(e) => Err(e),
         }
@@ -542,7 +592,7 @@ impl<'a> DynamixelControl<'a> {
         id: u8,
         data_name: ControlTable,
     ) -> Result<u16, CommunicationResult> {
-        match self.read(id, data_name, 2) {
+        match self.read_sized(id, data_name, 2) {
             Ok(v) => Ok(u16::from_le_bytes([v[0], v[1]])),
             Err(e) => Err(e),
         }
@@ -552,10 +602,10 @@ impl<'a> DynamixelControl<'a> {
         id: u8,
         data_name: ControlTable,
     ) -> Result<(), CommunicationResult> {
-        self.send_read_packet(id, data_name, 4)
+        self.send_sized_read_packet(id, data_name, 4)
     }
     pub fn receive_4byte_read_packet(&mut self, id: u8) -> Result<u32, CommunicationResult> {
-        match self.receive_read_packet(id, 4) {
+        match self.receive_status_packet(id, 4) {
             Ok(v) => Ok(u32::from_le_bytes([v[0], v[1], v[2], v[3]])),
             Err(e) => Err(e),
         }
@@ -565,7 +615,7 @@ impl<'a> DynamixelControl<'a> {
         id: u8,
         data_name: ControlTable,
     ) -> Result<u32, CommunicationResult> {
-        match self.read(id, data_name, 4) {
+        match self.read_sized(id, data_name, 4) {
             Ok(v) => Ok(u32::from_le_bytes([v[0], v[1], v[2], v[3]])),
             Err(e) => Err(e),
         }
@@ -577,37 +627,29 @@ impl<'a> DynamixelControl<'a> {
         data_name: ControlTable,
         data: &[u8],
     ) -> Result<(), CommunicationResult> {
-        if id >= BROADCAST_ID {
-            return Err(CommunicationResult::NotAvailable);
-        }
-
-        let address = data_name.to_address();
-        let size = data_name.to_size();
-        let length: u16 = 1 + 2 + (data.len() as u16) + 2; // instruction + adress + data + crc
+        self.send_write_instruction(Instruction::Write, id, data_name, data)
+    }
 
-        if size != data.len() as u16 {
-            return Err(CommunicationResult::NotAvailable);
+    fn send_write_instruction(
+        &mut self,
+        instruction: Instruction,
+        id: u8,
+        data_name: ControlTable,
+        data: &[u8],
+    ) -> Result<(), CommunicationResult> {
+        let msg = self
+            .registry
+            .write_packet(instruction, id, data_name, data)?;
+        match self.check_range(id, data_name, data) {
+            Ok(_) => {}
+            Err(e) => return Err(e),
         }
-
-        let mut msg = Vec::<u8, MAX_PACKET_LEN>::new();
-        msg.extend(self.reserve_msg_header().iter().cloned());
-        msg.push(id).unwrap();
-        msg.extend(length.to_le_bytes().iter().cloned()); // Set length temporary
-        msg.push(Instruction::Write as u8).unwrap();
-        msg.extend(address.to_le_bytes().iter().cloned());
-
-        // for d in data {
-        //     msg.push(*d).unwrap();
-        // }
-        msg.extend(data.iter().cloned());
-        let packet_len = msg.len() + 2;
-        match self.send_packet(msg) {
-            Ok(_) => {
-                self.set_packet_timeout_length(packet_len);
-                Ok(())
-            }
-            Err(e) => Err(e),
+        match self.transmit_packet(&msg, 0) {
+            Ok(_) => {}
+            Err(e) => return Err(e),
         }
+        self.registry.cache_write(instruction, id, data_name, data);
+        Ok(())
     }
 
     /// TxRx
@@ -621,34 +663,11 @@ impl<'a> DynamixelControl<'a> {
             Ok(_) => {}
             Err(e) => return Err(e),
         }
-        let status;
-        match self.receive_packet() {
-            Ok(v) => status = v,
+        match self.receive_status_packet(id, 0) {
+            Ok(_) => {}
             Err(e) => return Err(e),
         }
 
-        // header + id + length + instruction + err + param + crc
-        // id check
-        if status[Packet::Id.to_pos()] != id {
-            return Err(CommunicationResult::SomethingWentWrong);
-        }
-        // data length check
-        if u16::from_le_bytes([
-            status[Packet::LengthL.to_pos()],
-            status[Packet::LengthH.to_pos()],
-        ]) - 4
-            != 0
-        {
-            return Err(CommunicationResult::SomethingWentWrong);
-        }
-        // instruction check
-        if status[Packet::Instruction.to_pos()] != Instruction::Status as u8 {
-            return Err(CommunicationResult::SomethingWentWrong);
-        }
-        if status[Packet::Error.to_pos()] != 0x00 {
-            return Err(CommunicationResult::SomethingWentWrong);
-        }
-
         Ok(())
     }
 
@@ -701,100 +720,60 @@ impl<'a> DynamixelControl<'a> {
         self.write(id, data_name, &data.to_le_bytes())
     }
 
-    /// Reset all except ID and Baudrate.
-    /// Other reset type is not implemented yet.
-    pub fn factory_reset(&mut self, id: u8) -> Result<(), CommunicationResult> {
-        let length: u16 = 1 + 1 + 2; // instruction + param1 + crc
-        let mut msg = Vec::<u8, MAX_PACKET_LEN>::new();
+    /// Broadcast is not available for FactoryResetMode::ResetAll.
+    pub fn factory_reset(
+        &mut self,
+        id: u8,
+        mode: FactoryResetMode,
+    ) -> Result<(), CommunicationResult> {
+        if self.get_id_protocol_version(id) == ProtocolVersion::V1 {
+            return Err(CommunicationResult::NotAvailable);
+        }
+        if id == BROADCAST_ID && mode == FactoryResetMode::ResetAll {
+            // Devices ignore it to avoid all of them getting the same ID.
+            return Err(CommunicationResult::NotAvailable);
+        }
 
-        msg.extend(self.reserve_msg_header().iter().cloned());
-        msg.push(id).unwrap();
-        msg.extend(length.to_le_bytes().iter().cloned()); // Set length temporary
-        msg.push(Instruction::FactoryReset as u8).unwrap();
-        msg.push(0x02).unwrap(); // Reset all except ID and Baudrate
-        let packet_len = msg.len() + 2;
-        match self.send_packet(msg) {
+        let msg = build_packet(
+            ProtocolVersion::V2,
+            id,
+            Instruction::FactoryReset,
+            &[mode.to_value()],
+        )?;
+        match self.transmit_packet(&msg, 0) {
             Ok(_) => {
-                self.set_packet_timeout_length(packet_len);
+                self.registry.forget_torque_enable(id);
             }
             Err(e) => return Err(e),
         }
 
-        let status;
-        match self.receive_packet() {
-            Ok(v) => status = v,
-            Err(e) => return Err(e),
-        }
-
-        // header + id + length + instruction + err + param + crc
-        // id check
-        if status[Packet::Id.to_pos()] != id {
-            return Err(CommunicationResult::SomethingWentWrong);
-        }
-        // data length check
-        if u16::from_le_bytes([
-            status[Packet::LengthL.to_pos()],
-            status[Packet::LengthH.to_pos()],
-        ]) - 4
-            != 0
-        {
-            return Err(CommunicationResult::SomethingWentWrong);
+        // No status packet is returned for broadcast.
+        if id == BROADCAST_ID {
+            return Ok(());
         }
-        // instruction check
-        if status[Packet::Instruction.to_pos()] != Instruction::Status as u8 {
-            return Err(CommunicationResult::SomethingWentWrong);
-        }
-        if status[Packet::Error.to_pos()] != 0x00 {
-            return Err(CommunicationResult::SomethingWentWrong);
+        match self.receive_status_packet(id, 0) {
+            Ok(_) => Ok(()),
+            Err(e) => Err(e),
         }
-
-        Ok(())
     }
 
     pub fn reboot(&mut self, id: u8) -> Result<(), CommunicationResult> {
-        let length: u16 = 1 + 2; // instruction + crc
-        let mut msg = Vec::<u8, MAX_PACKET_LEN>::new();
-
-        msg.extend(self.reserve_msg_header().iter().cloned());
-        msg.push(id).unwrap();
-        msg.extend(length.to_le_bytes().iter().cloned()); // Set length temporary
-        msg.push(Instruction::Reboot as u8).unwrap();
-        let packet_len = msg.len() + 2;
-        match self.send_packet(msg) {
+        if self.get_id_protocol_version(id) == ProtocolVersion::V1 {
+            return Err(CommunicationResult::NotAvailable);
+        }
+        let msg = build_packet(ProtocolVersion::V2, id, Instruction::Reboot, &[])?;
+        match self.transmit_packet(&msg, 0) {
             Ok(_) => {
-                self.set_packet_timeout_length(packet_len);
+                self.registry.forget_torque_enable(id);
             }
             Err(e) => return Err(e),
         }
 
-        let status;
-        match self.receive_packet() {
-            Ok(v) => status = v,
+        match self.receive_status_packet(id, 0) {
+            Ok(_) => {}
             Err(e) => return Err(e),
         }
 
-        // header + id + length + instruction + err + param + crc
-        // id check
-        if status[Packet::Id.to_pos()] != id {
-            return Err(CommunicationResult::SomethingWentWrong);
-        }
-        // data length check
-        if u16::from_le_bytes([
-            status[Packet::LengthL.to_pos()],
-            status[Packet::LengthH.to_pos()],
-        ]) - 4
-            != 0
-        {
-            return Err(CommunicationResult::SomethingWentWrong);
-        }
-        // instruction check
-        if status[Packet::Instruction.to_pos()] != Instruction::Status as u8 {
-            return Err(CommunicationResult::SomethingWentWrong);
-        }
-        if status[Packet::Error.to_pos()] != 0x00 {
-            return Err(CommunicationResult::SomethingWentWrong);
-        }
-
         Ok(())
     }
 
@@ -804,31 +783,13 @@ impl<'a> DynamixelControl<'a> {
         data_name: ControlTable,
         data_size: u16,
     ) -> Result<(), CommunicationResult> {
-        let address = data_name.to_address();
-        let length: u16 = 1 + 2 + 2 + id.len() as u16 + 2; // instruction + address + length + ids + crc
-        let mut msg = Vec::<u8, MAX_PACKET_LEN>::new();
-
-        msg.extend(self.reserve_msg_header().iter().cloned());
-        msg.push(0xFE).unwrap(); // id
-        msg.extend(length.to_le_bytes().iter().cloned()); // Set length temporary
-        msg.push(Instruction::SyncRead as u8).unwrap();
-        msg.extend(address.to_le_bytes().iter().cloned());
-        msg.extend(data_size.to_le_bytes().iter().cloned());
-        for i in id {
-            msg.push(i.clone()).unwrap();
-        }
-
-        let packet_len = msg.len() + 2;
-
-        match self.send_packet(msg) {
-            Ok(_) => {
-                self.set_packet_timeout_length(packet_len);
-            }
-            Err(e) => return Err(e),
-        }
-
-        Ok(())
+        let msg =
+            self.registry
+                .sync_read_packet(Instruction::SyncRead, id, data_name, data_size)?;
+        self.transmit_packet(&msg, 0)
     }
+
+    /// Devices with different protocols are written by separate packets.
     pub fn send_sync_write_packet(
         &mut self,
         id: &[u8],
@@ -836,81 +797,363 @@ impl<'a> DynamixelControl<'a> {
         data_name: ControlTable,
         data_size: u16,
     ) -> Result<(), CommunicationResult> {
-        let address = data_name.to_address();
-        let length: u16 = 1 + 2 + 2 + id.len() as u16 + id.len() as u16 * data_size + 2; // instruction + address + length + ids + datas + crc
-        let mut msg = Vec::<u8, MAX_PACKET_LEN>::new();
-
-        msg.extend(self.reserve_msg_header().iter().cloned());
-        msg.push(0xFE).unwrap(); // id
-        msg.extend(length.to_le_bytes().iter().cloned()); // Set length temporary
-        msg.push(Instruction::SyncWrite as u8).unwrap();
-        msg.extend(address.to_le_bytes().iter().cloned());
-        msg.extend(data_size.to_le_bytes().iter().cloned());
-        for i in 0..id.len() {
-            msg.push(id[i].clone()).unwrap();
-            for j in 0..data_size as usize {
-                msg.push(data[i * data_size as usize + j]).unwrap();
+        let packets = self
+            .registry
+            .sync_write_packets(id, data, data_name, data_size)?;
+        for msg in packets.iter() {
+            match self.transmit_packet(msg, 0) {
+                Ok(_) => {}
+                Err(e) => return Err(e),
             }
         }
+        self.registry
+            .cache_sync_torque_enable(id, data, data_name, data_size);
 
-        let packet_len = msg.len() + 2;
+        Ok(())
+    }
 
-        match self.send_packet(msg) {
-            Ok(_) => {
-                self.set_packet_timeout_length(packet_len);
+    pub fn send_fast_sync_read_packet(
+        &mut self,
+        id: &[u8],
+        data_name: ControlTable,
+        data_size: u16,
+    ) -> Result<(), CommunicationResult> {
+        let msg =
+            self.registry
+                .sync_read_packet(Instruction::FastSyncRead, id, data_name, data_size)?;
+        // header + id + length + instruction + (err + id + data + crc) * n
+        let status_len = 8 + id.len() * (4 + data_size as usize);
+        self.transmit_packet(&msg, status_len)
+    }
+
+    /// Receive the single status packet answering a fast sync read.
+    pub fn receive_fast_sync_read_packet(&mut self, id: &[u8], data_size: u16) -> MultiReadResult {
+        let mut lengths = Vec::<(u8, u16), MAX_READ_ID_NUM>::new();
+        for i in id {
+            if lengths.push((*i, data_size)).is_err() {
+                break;
             }
+        }
+        self.receive_fast_read_packet(&lengths)
+    }
+
+    /// TxRx
+    pub fn fast_sync_read(
+        &mut self,
+        id: &[u8],
+        data_name: ControlTable,
+        data_size: u16,
+    ) -> Result<MultiReadResult, CommunicationResult> {
+        match self.send_fast_sync_read_packet(id, data_name, data_size) {
+            Ok(_) => {}
             Err(e) => return Err(e),
         }
+        Ok(self.receive_fast_sync_read_packet(id, data_size))
+    }
+
+    /// Receive the status packet of fast sync read and fast bulk read.
+    /// params: (id, data length)
+    fn receive_fast_read_packet(&mut self, params: &[(u8, u16)]) -> MultiReadResult {
+        let status = self.receive_packet(ProtocolVersion::V2);
+        let mut result = parse_fast_read_status(status, params);
+        self.diagnose_multi_read_alert(&mut result);
+        result
+    }
+
+    /// params: (id, data name, data length)
+    pub fn send_bulk_read_packet(
+        &mut self,
+        params: &[(u8, ControlTable, u16)],
+    ) -> Result<(), CommunicationResult> {
+        let msg = self
+            .registry
+            .bulk_read_packet(Instruction::BulkRead, params)?;
+        // Each device answers after the previous one.
+        let mut status_len = 0;
+        for (id, _, data_length) in params {
+            status_len += status_packet_len(self.get_id_protocol_version(*id), *data_length);
+        }
+        self.transmit_packet(&msg, status_len)
+    }
+
+    /// params: (id, data name, data length)
+    pub fn send_fast_bulk_read_packet(
+        &mut self,
+        params: &[(u8, ControlTable, u16)],
+    ) -> Result<(), CommunicationResult> {
+        let msg = self
+            .registry
+            .bulk_read_packet(Instruction::FastBulkRead, params)?;
+        // header + id + length + instruction + (err + id + data + crc) * n
+        let mut status_len = 8;
+        for (_, _, data_length) in params {
+            status_len += 4 + *data_length as usize;
+        }
+        self.transmit_packet(&msg, status_len)
+    }
+
+    /// Receive the status packets answering a bulk read.
+    /// params: (id, data length)
+    pub fn receive_bulk_read_packet(&mut self, params: &[(u8, u16)]) -> MultiReadResult {
+        let version = match self
+            .registry
+            .get_ids_protocol_version(params.iter().map(|p| p.0))
+        {
+            Some(ProtocolVersion::V1) => ProtocolVersion::V1,
+            _ => ProtocolVersion::V2,
+        };
+        let mut result = new_multi_read_result(params);
+        self.receive_multi_read_packet(version, params, &mut result);
+        self.diagnose_multi_read_alert(&mut result);
+        result
+    }
+
+    /// Receive the status packets of the devices using the protocol.
+    /// params: (id, data length)
+    fn receive_multi_read_packet(
+        &mut self,
+        version: ProtocolVersion,
+        params: &[(u8, u16)],
+        result: &mut MultiReadResult,
+    ) {
+        let mut remaining = result
+            .iter()
+            .filter(|(id, _)| self.get_id_protocol_version(*id) == version)
+            .count();
+        while remaining > 0 {
+            let status = match self.receive_packet(version) {
+                Ok(v) => v,
+                Err(CommunicationResult::RxCRCError) => continue,
+                Err(_) => break,
+            };
+            if parse_multi_read_status(version, &status, params, result) {
+                remaining -= 1;
+            }
+        }
+    }
+
+    /// TxRx
+    /// Devices with different protocols are read by separate packets.
+    /// params: (id, data name, data length)
+    pub fn bulk_read(
+        &mut self,
+        params: &[(u8, ControlTable, u16)],
+    ) -> Result<MultiReadResult, CommunicationResult> {
+        if params.len() > MAX_READ_ID_NUM {
+            return Err(CommunicationResult::NotAvailable);
+        }
+        let mut lengths = Vec::<(u8, u16), MAX_READ_ID_NUM>::new();
+        for (id, _, data_length) in params {
+            lengths.push((*id, *data_length)).unwrap();
+        }
+        let mut result = new_multi_read_result(&lengths);
+        for version in [ProtocolVersion::V2, ProtocolVersion::V1] {
+            let version_params = self.registry.get_version_params(params, version);
+            if version_params.is_empty() {
+                continue;
+            }
+            match self.send_bulk_read_packet(&version_params) {
+                Ok(_) => {}
+                Err(e) => return Err(e),
+            }
+            self.receive_multi_read_packet(version, &lengths, &mut result);
+        }
+
+        self.diagnose_multi_read_alert(&mut result);
+        Ok(result)
+    }
+
+    /// Receive the single status packet answering a fast bulk read.
+    /// params: (id, data length)
+    pub fn receive_fast_bulk_read_packet(&mut self, params: &[(u8, u16)]) -> MultiReadResult {
+        self.receive_fast_read_packet(params)
+    }
+
+    /// TxRx
+    /// params: (id, data name, data length)
+    pub fn fast_bulk_read(
+        &mut self,
+        params: &[(u8, ControlTable, u16)],
+    ) -> Result<MultiReadResult, CommunicationResult> {
+        match self.send_fast_bulk_read_packet(params) {
+            Ok(_) => {}
+            Err(e) => return Err(e),
+        }
+        let mut lengths = Vec::<(u8, u16), MAX_READ_ID_NUM>::new();
+        for (id, _, data_length) in params {
+            lengths.push((*id, *data_length)).unwrap();
+        }
+        Ok(self.receive_fast_bulk_read_packet(&lengths))
+    }
+
+    /// params: (id, data name, data)
+    pub fn send_bulk_write_packet(
+        &mut self,
+        params: &[(u8, ControlTable, &[u8])],
+    ) -> Result<(), CommunicationResult> {
+        let msg = self.registry.bulk_write_packet(params)?;
+        match self.transmit_packet(&msg, 0) {
+            Ok(_) => {}
+            Err(e) => return Err(e),
+        }
+        for (id, data_name, data) in params {
+            self.registry
+                .cache_write(Instruction::Write, *id, *data_name, data);
+        }
 
         Ok(())
     }
 
-    // bulkReadTx
-    // bulkWriteTxOnly
-    // pub fn broadcast_ping(&mut self) {}
-    // regWriteTxOnly
-    // regWriteTxRx
-    // pub fn action(&mut self) {}
-    // clear
-
-    fn calc_crc_value(&self, msg: &[u8]) -> u16 {
-        let crc_table = [
-            0x0000, 0x8005, 0x800F, 0x000A, 0x801B, 0x001E, 0x0014, 0x8011, 0x8033, 0x0036, 0x003C,
-            0x8039, 0x0028, 0x802D, 0x8027, 0x0022, 0x8063, 0x0066, 0x006C, 0x8069, 0x0078, 0x807D,
-            0x8077, 0x0072, 0x0050, 0x8055, 0x805F, 0x005A, 0x804B, 0x004E, 0x0044, 0x8041, 0x80C3,
-            0x00C6, 0x00CC, 0x80C9, 0x00D8, 0x80DD, 0x80D7, 0x00D2, 0x00F0, 0x80F5, 0x80FF, 0x00FA,
-            0x80EB, 0x00EE, 0x00E4, 0x80E1, 0x00A0, 0x80A5, 0x80AF, 0x00AA, 0x80BB, 0x00BE, 0x00B4,
-            0x80B1, 0x8093, 0x0096, 0x009C, 0x8099, 0x0088, 0x808D, 0x8087, 0x0082, 0x8183, 0x0186,
-            0x018C, 0x8189, 0x0198, 0x819D, 0x8197, 0x0192, 0x01B0, 0x81B5, 0x81BF, 0x01BA, 0x81AB,
-            0x01AE, 0x01A4, 0x81A1, 0x01E0, 0x81E5, 0x81EF, 0x01EA, 0x81FB, 0x01FE, 0x01F4, 0x81F1,
-            0x81D3, 0x01D6, 0x01DC, 0x81D9, 0x01C8, 0x81CD, 0x81C7, 0x01C2, 0x0140, 0x8145, 0x814F,
-            0x014A, 0x815B, 0x015E, 0x0154, 0x8151, 0x8173, 0x0176, 0x017C, 0x8179, 0x0168, 0x816D,
-            0x8167, 0x0162, 0x8123, 0x0126, 0x012C, 0x8129, 0x0138, 0x813D, 0x8137, 0x0132, 0x0110,
-            0x8115, 0x811F, 0x011A, 0x810B, 0x010E, 0x0104, 0x8101, 0x8303, 0x0306, 0x030C, 0x8309,
-            0x0318, 0x831D, 0x8317, 0x0312, 0x0330, 0x8335, 0x833F, 0x033A, 0x832B, 0x032E, 0x0324,
-            0x8321, 0x0360, 0x8365, 0x836F, 0x036A, 0x837B, 0x037E, 0x0374, 0x8371, 0x8353, 0x0356,
-            0x035C, 0x8359, 0x0348, 0x834D, 0x8347, 0x0342, 0x03C0, 0x83C5, 0x83CF, 0x03CA, 0x83DB,
-            0x03DE, 0x03D4, 0x83D1, 0x83F3, 0x03F6, 0x03FC, 0x83F9, 0x03E8, 0x83ED, 0x83E7, 0x03E2,
-            0x83A3, 0x03A6, 0x03AC, 0x83A9, 0x03B8, 0x83BD, 0x83B7, 0x03B2, 0x0390, 0x8395, 0x839F,
-            0x039A, 0x838B, 0x038E, 0x0384, 0x8381, 0x0280, 0x8285, 0x828F, 0x028A, 0x829B, 0x029E,
-            0x0294, 0x8291, 0x82B3, 0x02B6, 0x02BC, 0x82B9, 0x02A8, 0x82AD, 0x82A7, 0x02A2, 0x82E3,
-            0x02E6, 0x02EC, 0x82E9, 0x02F8, 0x82FD, 0x82F7, 0x02F2, 0x02D0, 0x82D5, 0x82DF, 0x02DA,
-            0x82CB, 0x02CE, 0x02C4, 0x82C1, 0x8243, 0x0246, 0x024C, 0x8249, 0x0258, 0x825D, 0x8257,
-            0x0252, 0x0270, 0x8275, 0x827F, 0x027A, 0x826B, 0x026E, 0x0264, 0x8261, 0x0220, 0x8225,
-            0x822F, 0x022A, 0x823B, 0x023E, 0x0234, 0x8231, 0x8213, 0x0216, 0x021C, 0x8219, 0x0208,
-            0x820D, 0x8207, 0x0202,
-        ];
-
-        let mut crc_accum = 0x0000;
-        for j in 0..msg.len() {
-            let i = ((((crc_accum >> 8) as u8) ^ msg[j]) & 0xFF) as usize;
-            crc_accum = (crc_accum << 8) ^ crc_table[i];
-        }
-
-        crc_accum
-    }
-
-    fn set_packet_timeout_length(&mut self, packet_length: usize) {
+    /// The data is written when action is received.
+    pub fn send_reg_write_packet(
+        &mut self,
+        id: u8,
+        data_name: ControlTable,
+        data: &[u8],
+    ) -> Result<(), CommunicationResult> {
+        self.send_write_instruction(Instruction::RegWrite, id, data_name, data)
+    }
+
+    /// TxRx
+    /// The data is written when action is received.
+    pub fn reg_write(
+        &mut self,
+        id: u8,
+        data_name: ControlTable,
+        data: &[u8],
+    ) -> Result<(), CommunicationResult> {
+        match self.send_reg_write_packet(id, data_name, data) {
+            Ok(_) => {}
+            Err(e) => return Err(e),
+        }
+        match self.receive_status_packet(id, 0) {
+            Ok(_) => Ok(()),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Execute the instruction registered by reg_write.
+    /// Use BROADCAST_ID to start all devices at once.
+    pub fn action(&mut self, id: u8) -> Result<(), CommunicationResult> {
+        if id == BROADCAST_ID {
+            // Send action in every protocol on the bus.
+            // No status packet is returned for broadcast.
+            for version in [ProtocolVersion::V2, ProtocolVersion::V1] {
+                if self.registry.is_protocol_version_used(version) {
+                    match self.send_action_packet(version, id) {
+                        Ok(_) => {}
+                        Err(e) => return Err(e),
+                    }
+                }
+            }
+            return Ok(());
+        }
+
+        match self.send_action_packet(self.get_id_protocol_version(id), id) {
+            Ok(_) => {}
+            Err(e) => return Err(e),
+        }
+        match self.receive_status_packet(id, 0) {
+            Ok(_) => Ok(()),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn send_action_packet(
+        &mut self,
+        version: ProtocolVersion,
+        id: u8,
+    ) -> Result<(), CommunicationResult> {
+        let msg = build_packet(version, id, Instruction::Action, &[])?;
+        self.transmit_packet(&msg, 0)
+    }
+
+    /// Reset the multi-turn revolution count of present position.
+    /// Only works while the device is stopped.
+    pub fn clear_multi_turn(&mut self, id: u8) -> Result<(), CommunicationResult> {
+        self.clear(id, &[0x01, 0x44, 0x58, 0x4C, 0x22]) // 0x01 + "DXL\""
+    }
+
+    /// Clear the error of the device.
+    /// Only available on models whose firmware supports it.
+    pub fn clear_error(&mut self, id: u8) -> Result<(), CommunicationResult> {
+        self.clear(id, &[0x02, 0x45, 0x52, 0x43, 0x4C]) // 0x02 + "ERCL"
+    }
+
+    fn clear(&mut self, id: u8, option: &[u8; 5]) -> Result<(), CommunicationResult> {
+        if self.get_id_protocol_version(id) == ProtocolVersion::V1 {
+            return Err(CommunicationResult::NotAvailable);
+        }
+        if id >= BROADCAST_ID {
+            return Err(CommunicationResult::NotAvailable);
+        }
+
+        let msg = build_packet(ProtocolVersion::V2, id, Instruction::Clear, option)?;
+        match self.transmit_packet(&msg, 0) {
+            Ok(_) => {}
+            Err(e) => return Err(e),
+        }
+
+        match self.receive_status_packet(id, 0) {
+            Ok(_) => Ok(()),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Store the control table to the backup area of the device.
+    /// Torque must be disabled.
+    pub fn backup_control_table(&mut self, id: u8) -> Result<(), CommunicationResult> {
+        self.control_table_backup(id, 0x01)
+    }
+
+    /// Restore the control table from the backup area of the device.
+    /// Torque must be disabled.
+    pub fn restore_control_table(&mut self, id: u8) -> Result<(), CommunicationResult> {
+        self.control_table_backup(id, 0x02)
+    }
+
+    fn control_table_backup(&mut self, id: u8, option: u8) -> Result<(), CommunicationResult> {
+        if self.get_id_protocol_version(id) == ProtocolVersion::V1 {
+            return Err(CommunicationResult::NotAvailable);
+        }
+        pub const BACKUP_TIMEOUT: u64 = 1_000; // msec
+        if id >= BROADCAST_ID {
+            return Err(CommunicationResult::NotAvailable);
+        }
+
+        let params = [option, 0x43, 0x54, 0x52, 0x4C]; // option + "CTRL"
+        let msg = build_packet(
+            ProtocolVersion::V2,
+            id,
+            Instruction::ControlTableBackup,
+            &params,
+        )?;
+        match self.transmit_packet(&msg, 0) {
+            Ok(_) => {}
+            Err(e) => return Err(e),
+        }
+
+        match self.receive_status_packet(id, 0) {
+            Ok(_) => {}
+            Err(e) => return Err(e),
+        }
+
+        // The device does not answer while it is busy with the backup.
+        let start_time = self.clock.get_current_time();
+        loop {
+            match self.read_1byte(id, ControlTable::BackupReady) {
+                Ok(1) => return Ok(()),
+                Ok(_) => {}
+                Err(CommunicationResult::RxTimeout) => {}
+                Err(e) => return Err(e),
+            }
+            if self.clock.get_current_time() > start_time + Duration::from_millis(BACKUP_TIMEOUT) {
+                return Err(CommunicationResult::RxTimeout);
+            }
+        }
+    }
+
+    pub(crate) fn set_packet_timeout_length(&mut self, packet_length: usize) {
         pub const LATENCY_CLOCK: u64 = 1_000; // usec
         self.packet_start_time = self.clock.get_current_time();
         let timeout_usec =
@@ -928,15 +1171,11 @@ impl<'a> DynamixelControl<'a> {
         self.packet_timeout = Duration::from_micros(usec)
     }
 
-    fn is_packet_timeout(&self) -> bool {
-        if self.clock.get_current_time() > self.packet_start_time + self.packet_timeout {
-            true
-        } else {
-            false
-        }
+    pub(crate) fn is_packet_timeout(&self) -> bool {
+        self.clock.get_current_time() > self.packet_start_time + self.packet_timeout
     }
 
-    fn clear_port(&mut self) {
+    pub(crate) fn clear_port(&mut self) {
         self.uart.clear_read_buf();
         // loop {
         //     match self.uart.read_byte() {
@@ -949,12 +1188,695 @@ impl<'a> DynamixelControl<'a> {
     }
 }
 
+impl DeviceRegistry {
+    /// Use broadcast_ping for BROADCAST_ID.
+    pub(crate) fn ping_packet(
+        &self,
+        id: u8,
+    ) -> Result<Vec<u8, MAX_PACKET_LEN>, CommunicationResult> {
+        if id >= BROADCAST_ID {
+            return Err(CommunicationResult::NotAvailable);
+        }
+        build_packet(self.get_id_protocol_version(id), id, Instruction::Ping, &[])
+    }
+
+    pub(crate) fn read_packet(
+        &self,
+        id: u8,
+        data_name: ControlTable,
+        data_size: u16,
+    ) -> Result<Vec<u8, MAX_PACKET_LEN>, CommunicationResult> {
+        if id >= BROADCAST_ID {
+            return Err(CommunicationResult::NotAvailable);
+        }
+        if self.get_id_protocol_version(id) == ProtocolVersion::V1 {
+            return self.protocol1_read_packet(id, data_name, data_size);
+        }
+
+        let address = self.get_address(id, data_name)?;
+        let mut params = Vec::<u8, MAX_PACKET_LEN>::new();
+        params.extend(address.to_le_bytes().iter().cloned());
+        params.extend(data_size.to_le_bytes().iter().cloned());
+        build_packet(ProtocolVersion::V2, id, Instruction::Read, &params)
+    }
+
+    /// instruction: Write or RegWrite
+    pub(crate) fn write_packet(
+        &self,
+        instruction: Instruction,
+        id: u8,
+        data_name: ControlTable,
+        data: &[u8],
+    ) -> Result<Vec<u8, MAX_PACKET_LEN>, CommunicationResult> {
+        if id >= BROADCAST_ID {
+            return Err(CommunicationResult::NotAvailable);
+        }
+        match self.check_writable(id, data_name) {
+            Ok(_) => {}
+            Err(e) => return Err(e),
+        }
+        if self.get_id_protocol_version(id) == ProtocolVersion::V1 {
+            return self.protocol1_write_packet(instruction, id, data_name, data);
+        }
+
+        let address = self.get_address(id, data_name)?;
+        if data_name.to_size() != data.len() as u16 {
+            return Err(CommunicationResult::NotAvailable);
+        }
+        let mut params = Vec::<u8, MAX_PACKET_LEN>::new();
+        params.extend(address.to_le_bytes().iter().cloned());
+        params.extend(data.iter().cloned());
+        build_packet(ProtocolVersion::V2, id, instruction, &params)
+    }
+
+    /// instruction: SyncRead or FastSyncRead
+    pub(crate) fn sync_read_packet(
+        &self,
+        instruction: Instruction,
+        id: &[u8],
+        data_name: ControlTable,
+        data_size: u16,
+    ) -> Result<Vec<u8, MAX_PACKET_LEN>, CommunicationResult> {
+        if self.get_ids_protocol_version(id.iter().cloned()) != Some(ProtocolVersion::V2) {
+            return Err(CommunicationResult::NotAvailable);
+        }
+        // The status packet of fast sync read is split into MultiReadResult.
+        if instruction == Instruction::FastSyncRead && id.len() > MAX_READ_ID_NUM {
+            return Err(CommunicationResult::NotAvailable);
+        }
+
+        let address = self.get_ids_address(id, data_name)?;
+        let mut params = Vec::<u8, MAX_PACKET_LEN>::new();
+        params.extend(address.to_le_bytes().iter().cloned());
+        params.extend(data_size.to_le_bytes().iter().cloned());
+        push_params(&mut params, id)?;
+        build_packet(ProtocolVersion::V2, BROADCAST_ID, instruction, &params)
+    }
+
+    /// Devices with different protocols are written by separate packets.
+    pub(crate) fn sync_write_packets(
+        &self,
+        id: &[u8],
+        data: &[u8],
+        data_name: ControlTable,
+        data_size: u16,
+    ) -> Result<Vec<Vec<u8, MAX_PACKET_LEN>, 2>, CommunicationResult> {
+        if data_name.to_access() == Access::ReadOnly {
+            return Err(CommunicationResult::ReadOnly);
+        }
+        if data.len() != id.len() * data_size as usize {
+            return Err(CommunicationResult::NotAvailable);
+        }
+
+        let mut packets = Vec::<Vec<u8, MAX_PACKET_LEN>, 2>::new();
+        if let Some(version) = self.get_ids_protocol_version(id.iter().cloned()) {
+            let msg = self.sync_write_packet(version, id, data, data_name, data_size)?;
+            packets.push(msg).unwrap();
+            return Ok(packets);
+        }
+        for version in [ProtocolVersion::V2, ProtocolVersion::V1] {
+            let mut version_id = Vec::<u8, MAX_DEVICE_NUM>::new();
+            let mut version_data = Vec::<u8, MAX_PACKET_LEN>::new();
+            for i in 0..id.len() {
+                if self.get_id_protocol_version(id[i]) != version {
+                    continue;
+                }
+                let chunk = &data[i * data_size as usize..(i + 1) * data_size as usize];
+                if version_id.push(id[i]).is_err() {
+                    return Err(CommunicationResult::NotAvailable);
+                }
+                push_params(&mut version_data, chunk)?;
+            }
+            let msg =
+                self.sync_write_packet(version, &version_id, &version_data, data_name, data_size)?;
+            packets.push(msg).unwrap();
+        }
+        Ok(packets)
+    }
+
+    fn sync_write_packet(
+        &self,
+        version: ProtocolVersion,
+        id: &[u8],
+        data: &[u8],
+        data_name: ControlTable,
+        data_size: u16,
+    ) -> Result<Vec<u8, MAX_PACKET_LEN>, CommunicationResult> {
+        if version == ProtocolVersion::V1 {
+            return self.protocol1_sync_write_packet(id, data, data_name, data_size);
+        }
+
+        let address = self.get_ids_address(id, data_name)?;
+        let mut params = Vec::<u8, MAX_PACKET_LEN>::new();
+        params.extend(address.to_le_bytes().iter().cloned());
+        params.extend(data_size.to_le_bytes().iter().cloned());
+        for i in 0..id.len() {
+            push_params(&mut params, &[id[i]])?;
+            push_params(
+                &mut params,
+                &data[i * data_size as usize..(i + 1) * data_size as usize],
+            )?;
+        }
+        build_packet(
+            ProtocolVersion::V2,
+            BROADCAST_ID,
+            Instruction::SyncWrite,
+            &params,
+        )
+    }
+
+    /// instruction: BulkRead or FastBulkRead
+    /// params: (id, data name, data length)
+    pub(crate) fn bulk_read_packet(
+        &self,
+        instruction: Instruction,
+        params: &[(u8, ControlTable, u16)],
+    ) -> Result<Vec<u8, MAX_PACKET_LEN>, CommunicationResult> {
+        if params.len() > MAX_READ_ID_NUM {
+            return Err(CommunicationResult::NotAvailable);
+        }
+        match self.get_ids_protocol_version(params.iter().map(|p| p.0)) {
+            Some(ProtocolVersion::V1) if instruction == Instruction::BulkRead => {
+                return self.protocol1_bulk_read_packet(params)
+            }
+            Some(ProtocolVersion::V2) => {}
+            // Use bulk_read for devices with different protocols.
+            _ => return Err(CommunicationResult::NotAvailable),
+        }
+
+        let mut bulk_params = Vec::<u8, MAX_PACKET_LEN>::new();
+        for (id, data_name, data_length) in params {
+            let address = self.get_address(*id, *data_name)?;
+            bulk_params.push(*id).unwrap();
+            bulk_params.extend(address.to_le_bytes().iter().cloned());
+            bulk_params.extend(data_length.to_le_bytes().iter().cloned());
+        }
+        build_packet(ProtocolVersion::V2, BROADCAST_ID, instruction, &bulk_params)
+    }
+
+    /// Bulk read params of the devices using the protocol.
+    pub(crate) fn get_version_params(
+        &self,
+        params: &[(u8, ControlTable, u16)],
+        version: ProtocolVersion,
+    ) -> Vec<(u8, ControlTable, u16), MAX_READ_ID_NUM> {
+        let mut result = Vec::<(u8, ControlTable, u16), MAX_READ_ID_NUM>::new();
+        for param in params {
+            if self.get_id_protocol_version(param.0) == version && result.push(*param).is_err() {
+                break;
+            }
+        }
+        result
+    }
+
+    /// params: (id, data name, data)
+    pub(crate) fn bulk_write_packet(
+        &self,
+        params: &[(u8, ControlTable, &[u8])],
+    ) -> Result<Vec<u8, MAX_PACKET_LEN>, CommunicationResult> {
+        if params.iter().any(|p| p.1.to_access() == Access::ReadOnly) {
+            return Err(CommunicationResult::ReadOnly);
+        }
+        if params.iter().any(|p| p.2.len() != p.1.to_size() as usize) {
+            return Err(CommunicationResult::NotAvailable);
+        }
+        if self.get_ids_protocol_version(params.iter().map(|p| p.0)) != Some(ProtocolVersion::V2) {
+            return Err(CommunicationResult::NotAvailable);
+        }
+
+        let mut bulk_params = Vec::<u8, MAX_PACKET_LEN>::new();
+        for (id, data_name, data) in params {
+            let address = self.get_address(*id, *data_name)?;
+            push_params(&mut bulk_params, &[*id])?;
+            push_params(&mut bulk_params, &address.to_le_bytes())?;
+            push_params(&mut bulk_params, &(data.len() as u16).to_le_bytes())?;
+            push_params(&mut bulk_params, data)?;
+        }
+        build_packet(
+            ProtocolVersion::V2,
+            BROADCAST_ID,
+            Instruction::BulkWrite,
+            &bulk_params,
+        )
+    }
+}
+
+/// Build the instruction packet with the header, the length and CRC or checksum.
+/// NotAvailable when the packet exceeds MAX_PACKET_LEN.
+pub(crate) fn build_packet(
+    version: ProtocolVersion,
+    id: u8,
+    instruction: Instruction,
+    params: &[u8],
+) -> Result<Vec<u8, MAX_PACKET_LEN>, CommunicationResult> {
+    if version == ProtocolVersion::V1 {
+        return build_protocol1_packet(id, instruction, params);
+    }
+    let length = 1 + params.len() + 2; // instruction + params + crc
+                                       // header + id + length + (instruction + params + crc)
+    if Packet::LengthH.to_pos() + 1 + length > MAX_PACKET_LEN {
+        return Err(CommunicationResult::NotAvailable);
+    }
+
+    let mut msg = Vec::<u8, MAX_PACKET_LEN>::new();
+    msg.extend([0x00; 4].iter().cloned()); // Header and reserved
+    msg.push(id).unwrap();
+    msg.extend((length as u16).to_le_bytes().iter().cloned());
+    msg.push(instruction as u8).unwrap();
+    msg.extend(params.iter().cloned());
+    finish_packet(&mut msg)?;
+    Ok(msg)
+}
+
+/// Add stuffing, header and CRC to the packet without CRC.
+pub(crate) fn finish_packet(msg: &mut Vec<u8, MAX_PACKET_LEN>) -> Result<(), CommunicationResult> {
+    add_stuffing(msg)?;
+
+    // make header
+    msg[Packet::Header0.to_pos()] = 0xFF;
+    msg[Packet::Header1.to_pos()] = 0xFF;
+    msg[Packet::Header2.to_pos()] = 0xFD;
+    msg[Packet::Reserved.to_pos()] = 0x00;
+
+    // add crc
+    let crc = calc_crc_value(msg);
+    match msg.extend_from_slice(&crc.to_le_bytes()) {
+        Ok(_) => Ok(()),
+        Err(_) => Err(CommunicationResult::NotAvailable),
+    }
+}
+
+/// NotAvailable when the parameters exceed MAX_PACKET_LEN.
+pub(crate) fn push_params(
+    params: &mut Vec<u8, MAX_PACKET_LEN>,
+    data: &[u8],
+) -> Result<(), CommunicationResult> {
+    match params.extend_from_slice(data) {
+        Ok(_) => Ok(()),
+        Err(_) => Err(CommunicationResult::NotAvailable),
+    }
+}
+
+/// Length of the status packet with data_length bytes of parameters.
+pub(crate) fn status_packet_len(version: ProtocolVersion, data_length: u16) -> usize {
+    match version {
+        // header + id + length + err + param + checksum
+        ProtocolVersion::V1 => 6 + data_length as usize,
+        // header + id + length + instruction + err + param + crc
+        ProtocolVersion::V2 => 11 + data_length as usize,
+    }
+}
+
+/// BusCollision when the echo differs from the transmitted packet.
+pub(crate) fn check_echo(msg: &[u8], echo: &[u8]) -> Result<(), CommunicationResult> {
+    if echo[..] != msg[..echo.len()] {
+        return Err(CommunicationResult::BusCollision);
+    }
+    Ok(())
+}
+
+/// Check the status packet from the device and return its parameters.
+pub(crate) fn parse_status_packet(
+    version: ProtocolVersion,
+    status: &[u8],
+    id: u8,
+    data_length: u16,
+    ignore_alert: bool,
+) -> Result<Vec<u8, MAX_PACKET_LEN>, CommunicationResult> {
+    if version == ProtocolVersion::V1 {
+        return parse_protocol1_status_packet(status, id, data_length);
+    }
+
+    // header + id + length + instruction + err + param + crc
+    // id check
+    if status[Packet::Id.to_pos()] != id {
+        return Err(CommunicationResult::SomethingWentWrong);
+    }
+    // instruction check
+    if status[Packet::Instruction.to_pos()] != Instruction::Status as u8 {
+        return Err(CommunicationResult::SomethingWentWrong);
+    }
+    // error check comes first, because status with error may have no param
+    let mut error = status[Packet::Error.to_pos()];
+    if ignore_alert {
+        error &= 0x7F;
+    }
+    match CommunicationResult::from_status_error(error) {
+        Ok(_) => {}
+        Err(e) => return Err(e),
+    }
+    // data length check
+    let length = u16::from_le_bytes([
+        status[Packet::LengthL.to_pos()],
+        status[Packet::LengthH.to_pos()],
+    ]);
+    if length.checked_sub(4) != Some(data_length) {
+        return Err(CommunicationResult::SomethingWentWrong);
+    }
+
+    let mut data = Vec::<u8, MAX_PACKET_LEN>::new();
+    data.extend(
+        status[(Packet::Error.to_pos() + 1)..(Packet::Error.to_pos() + 1 + data_length as usize)]
+            .iter()
+            .cloned(),
+    );
+    Ok(data)
+}
+
+/// Result of each device waiting for its status packet.
+/// params: (id, data length)
+pub(crate) fn new_multi_read_result(params: &[(u8, u16)]) -> MultiReadResult {
+    let mut result = MultiReadResult::new();
+    for (id, _) in params {
+        if result
+            .push((*id, Err(CommunicationResult::RxTimeout)))
+            .is_err()
+        {
+            break;
+        }
+    }
+    result
+}
+
+/// Put the status packet answering a sync read or a bulk read into the result of the device.
+/// false when the device is not waiting for it.
+/// params: (id, data length)
+pub(crate) fn parse_multi_read_status(
+    version: ProtocolVersion,
+    status: &[u8],
+    params: &[(u8, u16)],
+    result: &mut MultiReadResult,
+) -> bool {
+    let id = match version {
+        ProtocolVersion::V1 => status[Protocol1Packet::Id.to_pos()],
+        ProtocolVersion::V2 => status[Packet::Id.to_pos()],
+    };
+    let idx = match params.iter().position(|(i, _)| *i == id) {
+        Some(v) if v < result.len() => v,
+        _ => return false,
+    };
+    if result[idx].1 != Err(CommunicationResult::RxTimeout) {
+        return false;
+    }
+
+    result[idx].1 = match parse_status_packet(version, status, id, params[idx].1, false) {
+        // Only the data length can be wrong
+        Err(CommunicationResult::SomethingWentWrong) => Err(CommunicationResult::RxCorrupt),
+        v => v,
+    };
+    true
+}
+
+/// Split the status packet of fast sync read and fast bulk read into the data of each device.
+/// params: (id, data length)
+pub(crate) fn parse_fast_read_status(
+    status: Result<Vec<u8, MAX_PACKET_LEN>, CommunicationResult>,
+    params: &[(u8, u16)],
+) -> MultiReadResult {
+    // instruction + (err + id + data + crc) * n
+    let mut length: usize = 1;
+    for (_, data_length) in params {
+        length += 4 + *data_length as usize;
+    }
+
+    let status = match status {
+        Ok(v) => {
+            if v[Packet::Id.to_pos()] != BROADCAST_ID
+                || u16::from_le_bytes([v[Packet::LengthL.to_pos()], v[Packet::LengthH.to_pos()]])
+                    as usize
+                    != length
+            {
+                Err(CommunicationResult::RxCorrupt)
+            } else {
+                Ok(v)
+            }
+        }
+        Err(e) => Err(e),
+    };
+
+    let mut result = MultiReadResult::new();
+    let mut idx = Packet::Error.to_pos();
+    for (id, data_length) in params {
+        // Each device appends the CRC of the packet from the header to its data.
+        let crc_pos = idx + 2 + *data_length as usize;
+        let data = match &status {
+            Ok(status) => {
+                if calc_stuffed_crc_value(&status[..crc_pos])
+                    != u16::from_le_bytes([status[crc_pos], status[crc_pos + 1]])
+                {
+                    Err(CommunicationResult::RxCRCError)
+                } else if status[idx + 1] != *id {
+                    Err(CommunicationResult::RxCorrupt)
+                } else if let Err(e) = CommunicationResult::from_status_error(status[idx]) {
+                    Err(e)
+                } else {
+                    let mut data = Vec::<u8, MAX_PACKET_LEN>::new();
+                    data.extend(
+                        status[(idx + 2)..(idx + 2 + *data_length as usize)]
+                            .iter()
+                            .cloned(),
+                    );
+                    Ok(data)
+                }
+            }
+            Err(e) => Err(*e),
+        };
+        idx += 4 + *data_length as usize;
+        if result.push((*id, data)).is_err() {
+            break;
+        }
+    }
+
+    result
+}
+
+/// NotAvailable when the stuffed packet exceeds MAX_PACKET_LEN.
+pub(crate) fn add_stuffing(msg: &mut Vec<u8, MAX_PACKET_LEN>) -> Result<(), CommunicationResult> {
+    let packet_length_in =
+        u16::from_le_bytes([msg[Packet::LengthL.to_pos()], msg[Packet::LengthH.to_pos()]]);
+    let mut packet_length_out = packet_length_in;
+
+    if packet_length_in < 8 {
+        // INSTRUCTION, ADDR_L, ADDR_H, CRC16_L, CRC16_H + FF FF FD
+        return Ok(());
+    }
+
+    let packet_length_before_crc = packet_length_in - 2;
+    for i in 3..packet_length_before_crc as usize {
+        let check = i + Packet::Instruction.to_pos() - 2;
+        if msg[check] == 0xFF && msg[check + 1] == 0xFF && msg[check + 2] == 0xFD {
+            packet_length_out += 1;
+        }
+    }
+
+    if packet_length_in == packet_length_out {
+        // no stuffing required
+        return Ok(());
+    }
+    // header + id + length + (instruction + params + crc)
+    if Packet::LengthH.to_pos() + 1 + packet_length_out as usize > MAX_PACKET_LEN {
+        return Err(CommunicationResult::NotAvailable);
+    }
+    msg.resize(
+        msg.len() + packet_length_out as usize - packet_length_in as usize,
+        0,
+    )
+    .unwrap();
+
+    let mut out_index = packet_length_out as usize + 6 - 2; // last index before crc
+    let mut in_index = packet_length_in as usize + 6 - 2; // last index before crc
+    while out_index != in_index {
+        if msg[in_index] == 0xFD && msg[in_index - 1] == 0xFF && msg[in_index - 2] == 0xFF {
+            msg[out_index] = 0xFD; // byte stuffing
+            out_index -= 1;
+            if out_index != in_index {
+                msg[out_index] = msg[in_index]; // FD
+                out_index -= 1;
+                in_index -= 1;
+                msg[out_index] = msg[in_index]; // FF
+                out_index -= 1;
+                in_index -= 1;
+                msg[out_index] = msg[in_index]; // FF
+                out_index -= 1;
+                in_index -= 1;
+            }
+        } else {
+            msg[out_index] = msg[in_index];
+            out_index -= 1;
+            in_index -= 1;
+        }
+    }
+
+    msg[Packet::LengthL.to_pos()] = packet_length_out.to_le_bytes()[0];
+    msg[Packet::LengthH.to_pos()] = packet_length_out.to_le_bytes()[1];
+    Ok(())
+}
+
+pub(crate) fn remove_stuffing(msg: &mut Vec<u8, MAX_PACKET_LEN>) {
+    let packet_length_in =
+        u16::from_le_bytes([msg[Packet::LengthL.to_pos()], msg[Packet::LengthH.to_pos()]]);
+    let mut packet_length_out = packet_length_in;
+    // except CRC
+    let packet_length_before_crc = match packet_length_in.checked_sub(2) {
+        Some(v) => v,
+        None => return,
+    };
+
+    let mut index = Packet::Instruction.to_pos();
+    let mut i = 0;
+    while i < packet_length_before_crc as usize {
+        if msg[i + Packet::Instruction.to_pos()] == 0xFD
+            && msg[i + Packet::Instruction.to_pos() + 1] == 0xFD
+            && msg[i + Packet::Instruction.to_pos() - 1] == 0xFF
+            && msg[i + Packet::Instruction.to_pos() - 2] == 0xFF
+        {
+            // FF FF FD FD
+            packet_length_out -= 1;
+            i += 1;
+        }
+        msg[index] = msg[i + Packet::Instruction.to_pos()];
+        index += 1;
+        i += 1;
+    }
+
+    msg[index] = msg[Packet::Instruction.to_pos() + packet_length_before_crc as usize];
+    index += 1;
+    msg[index] = msg[Packet::Instruction.to_pos() + packet_length_before_crc as usize + 1];
+    index += 1;
+
+    msg[Packet::LengthL.to_pos()] = packet_length_out.to_le_bytes()[0];
+    msg[Packet::LengthH.to_pos()] = packet_length_out.to_le_bytes()[1];
+    msg.resize(index, 0).unwrap();
+}
+
+pub(crate) fn calc_crc_value(msg: &[u8]) -> u16 {
+    let crc_table = [
+        0x0000, 0x8005, 0x800F, 0x000A, 0x801B, 0x001E, 0x0014, 0x8011, 0x8033, 0x0036, 0x003C,
+        0x8039, 0x0028, 0x802D, 0x8027, 0x0022, 0x8063, 0x0066, 0x006C, 0x8069, 0x0078, 0x807D,
+        0x8077, 0x0072, 0x0050, 0x8055, 0x805F, 0x005A, 0x804B, 0x004E, 0x0044, 0x8041, 0x80C3,
+        0x00C6, 0x00CC, 0x80C9, 0x00D8, 0x80DD, 0x80D7, 0x00D2, 0x00F0, 0x80F5, 0x80FF, 0x00FA,
+        0x80EB, 0x00EE, 0x00E4, 0x80E1, 0x00A0, 0x80A5, 0x80AF, 0x00AA, 0x80BB, 0x00BE, 0x00B4,
+        0x80B1, 0x8093, 0x0096, 0x009C, 0x8099, 0x0088, 0x808D, 0x8087, 0x0082, 0x8183, 0x0186,
+        0x018C, 0x8189, 0x0198, 0x819D, 0x8197, 0x0192, 0x01B0, 0x81B5, 0x81BF, 0x01BA, 0x81AB,
+        0x01AE, 0x01A4, 0x81A1, 0x01E0, 0x81E5, 0x81EF, 0x01EA, 0x81FB, 0x01FE, 0x01F4, 0x81F1,
+        0x81D3, 0x01D6, 0x01DC, 0x81D9, 0x01C8, 0x81CD, 0x81C7, 0x01C2, 0x0140, 0x8145, 0x814F,
+        0x014A, 0x815B, 0x015E, 0x0154, 0x8151, 0x8173, 0x0176, 0x017C, 0x8179, 0x0168, 0x816D,
+        0x8167, 0x0162, 0x8123, 0x0126, 0x012C, 0x8129, 0x0138, 0x813D, 0x8137, 0x0132, 0x0110,
+        0x8115, 0x811F, 0x011A, 0x810B, 0x010E, 0x0104, 0x8101, 0x8303, 0x0306, 0x030C, 0x8309,
+        0x0318, 0x831D, 0x8317, 0x0312, 0x0330, 0x8335, 0x833F, 0x033A, 0x832B, 0x032E, 0x0324,
+        0x8321, 0x0360, 0x8365, 0x836F, 0x036A, 0x837B, 0x037E, 0x0374, 0x8371, 0x8353, 0x0356,
+        0x035C, 0x8359, 0x0348, 0x834D, 0x8347, 0x0342, 0x03C0, 0x83C5, 0x83CF, 0x03CA, 0x83DB,
+        0x03DE, 0x03D4, 0x83D1, 0x83F3, 0x03F6, 0x03FC, 0x83F9, 0x03E8, 0x83ED, 0x83E7, 0x03E2,
+        0x83A3, 0x03A6, 0x03AC, 0x83A9, 0x03B8, 0x83BD, 0x83B7, 0x03B2, 0x0390, 0x8395, 0x839F,
+        0x039A, 0x838B, 0x038E, 0x0384, 0x8381, 0x0280, 0x8285, 0x828F, 0x028A, 0x829B, 0x029E,
+        0x0294, 0x8291, 0x82B3, 0x02B6, 0x02BC, 0x82B9, 0x02A8, 0x82AD, 0x82A7, 0x02A2, 0x82E3,
+        0x02E6, 0x02EC, 0x82E9, 0x02F8, 0x82FD, 0x82F7, 0x02F2, 0x02D0, 0x82D5, 0x82DF, 0x02DA,
+        0x82CB, 0x02CE, 0x02C4, 0x82C1, 0x8243, 0x0246, 0x024C, 0x8249, 0x0258, 0x825D, 0x8257,
+        0x0252, 0x0270, 0x8275, 0x827F, 0x027A, 0x826B, 0x026E, 0x0264, 0x8261, 0x0220, 0x8225,
+        0x822F, 0x022A, 0x823B, 0x023E, 0x0234, 0x8231, 0x8213, 0x0216, 0x021C, 0x8219, 0x0208,
+        0x820D, 0x8207, 0x0202,
+    ];
+
+    let mut crc_accum = 0x0000;
+    for m in msg {
+        let i = (((crc_accum >> 8) as u8) ^ m) as usize;
+        crc_accum = (crc_accum << 8) ^ crc_table[i];
+    }
+
+    crc_accum
+}
+
+/// CRC of the received bytes whose stuffing has been removed.
+fn calc_stuffed_crc_value(msg: &[u8]) -> u16 {
+    let mut stuffed = Vec::<u8, MAX_PACKET_LEN>::new();
+    for i in 0..msg.len() {
+        // The received bytes were not longer than this with stuffing.
+        stuffed.push(msg[i]).unwrap();
+        if i >= Packet::Instruction.to_pos() + 3 && msg[i - 2..=i] == [0xFF, 0xFF, 0xFD] {
+            stuffed.push(0xFD).unwrap();
+        }
+    }
+    calc_crc_value(&stuffed)
+}
+
+/// Find the status packet at the beginning of the received bytes, dropping the bytes before its header.
+/// Ok(true) when the whole packet is received, Ok(false) when wait_length bytes are needed.
+pub(crate) fn find_status_packet(
+    msg: &mut Vec<u8, MAX_PACKET_LEN>,
+    wait_length: &mut usize,
+) -> Result<bool, CommunicationResult> {
+    loop {
+        if msg.len() < *wait_length {
+            return Ok(false);
+        }
+        let mut idx = 0;
+        // find packet header
+        while idx < (msg.len() - 3) {
+            if msg[idx + Packet::Header0.to_pos()] == 0xFF
+                && msg[idx + Packet::Header1.to_pos()] == 0xFF
+                && msg[idx + Packet::Header2.to_pos()] == 0xFD
+                && msg[idx + Packet::Reserved.to_pos()] == 0x00
+            {
+                break;
+            }
+            idx += 1;
+        }
+
+        if idx != 0 {
+            // remove unnecessary packets
+            for s in 0..(msg.len() - idx) {
+                msg[s] = msg[idx + s];
+            }
+            msg.truncate(msg.len() - idx);
+            continue;
+        }
+
+        // found at the beginning of the packet
+        // Status of fast sync read and fast bulk read comes with BROADCAST_ID.
+        let packet_length =
+            u16::from_le_bytes([msg[Packet::LengthL.to_pos()], msg[Packet::LengthH.to_pos()]])
+                as usize;
+        if msg[Packet::Reserved.to_pos()] != 0x00
+            || (msg[Packet::Id.to_pos()] > MAX_ID && msg[Packet::Id.to_pos()] != BROADCAST_ID)
+            // instruction + err + crc at least
+            || !(4..=MAX_PACKET_LEN).contains(&packet_length)
+            || msg[Packet::Instruction.to_pos()] != 0x55
+        {
+            // remove the first byte in the packet
+            for s in 0..msg.len() - 1 {
+                msg[s] = msg[s + 1];
+            }
+            msg.truncate(msg.len() - 1);
+            continue;
+        }
+        // re-calculate the exact length of the rx packet
+        if *wait_length != packet_length + Packet::LengthH.to_pos() + 1 {
+            *wait_length = packet_length + Packet::LengthH.to_pos() + 1;
+            continue;
+        }
+
+        // verify CRC16
+        let crc = u16::from_le_bytes([msg[msg.len() - 2], msg[msg.len() - 1]]);
+        if calc_crc_value(&msg[..msg.len() - 2]) == crc {
+            return Ok(true);
+        } else {
+            return Err(CommunicationResult::RxCRCError);
+        }
+    }
+}
+
 #[cfg(test)]
+#[allow(clippy::bool_assert_comparison, clippy::needless_range_loop)]
 mod tests {
+    use crate::packet_handler::add_stuffing;
+    use crate::packet_handler::calc_crc_value;
+    use crate::packet_handler::parse_status_packet;
+    use crate::packet_handler::remove_stuffing;
+    use crate::packet_handler::CommunicationResult;
+    use crate::packet_handler::ErrorBit;
     use crate::packet_handler::MAX_PACKET_LEN;
-    use crate::ControlTable;
     use crate::DynamixelControl;
-    use crate::Instruction;
+    use crate::ProtocolVersion;
     use core::cell::RefCell;
     use core::time::Duration;
     use heapless::Deque;
@@ -1020,9 +1942,6 @@ mod tests {
 
     #[test]
     fn crc() {
-        let mut mock_uart = MockSerial::new();
-        let mock_clock = MockClock::new();
-        let dxl = DynamixelControl::new(&mut mock_uart, &mock_clock, 115200);
         let mut msg = Vec::<u8, MAX_PACKET_LEN>::new();
         msg.extend(
             [
@@ -1031,29 +1950,23 @@ mod tests {
             .iter()
             .cloned(),
         );
-        assert_eq!(dxl.calc_crc_value(&msg), 0x5D65);
+        assert_eq!(calc_crc_value(&msg), 0x5D65);
     }
 
     #[test]
     #[ignore]
     fn calc_crc() {
-        let mut mock_uart = MockSerial::new();
-        let mock_clock = MockClock::new();
-        let dxl = DynamixelControl::new(&mut mock_uart, &mock_clock, 115200);
         let mut msg = Vec::<u8, MAX_PACKET_LEN>::new();
         msg.extend(
             [0xFF, 0xFF, 0xFD, 0x00, 0x01, 0x04, 0x00, 0x06, 0x02]
                 .iter()
                 .cloned(),
         );
-        assert_eq!(dxl.calc_crc_value(&msg), 0x0000);
+        assert_eq!(calc_crc_value(&msg), 0x0000);
     }
 
     #[test]
     fn stuffing() {
-        let mut mock_uart = MockSerial::new();
-        let mock_clock = MockClock::new();
-        let mut dxl = DynamixelControl::new(&mut mock_uart, &mock_clock, 115200);
         let mut msg = Vec::<u8, MAX_PACKET_LEN>::new();
         msg.extend(
             [
@@ -1063,7 +1976,7 @@ mod tests {
             .iter()
             .cloned(),
         );
-        dxl.add_stuffing(&mut msg);
+        assert_eq!(add_stuffing(&mut msg), Ok(()));
         assert_eq!(
             *msg,
             [
@@ -1071,7 +1984,7 @@ mod tests {
                 0xFD, 0xFD, 0x01, 0x00, 0x00
             ]
         );
-        dxl.remove_stuffing(&mut msg);
+        remove_stuffing(&mut msg);
         assert_eq!(
             *msg,
             [
@@ -1081,6 +1994,46 @@ mod tests {
         );
     }
 
+    #[test]
+    fn short_status() {
+        // Length is shorter than instruction + err + crc
+        let mut msg = Vec::<u8, MAX_PACKET_LEN>::new();
+        msg.extend(
+            [0xFF, 0xFF, 0xFD, 0x00, 0x01, 0x01, 0x00, 0x55, 0x00]
+                .iter()
+                .cloned(),
+        );
+        remove_stuffing(&mut msg);
+        assert_eq!(msg.len(), 9);
+        assert_eq!(
+            parse_status_packet(ProtocolVersion::V2, &msg, 1, 0, false),
+            Err(CommunicationResult::SomethingWentWrong)
+        );
+    }
+
+    #[test]
+    fn error_bit() {
+        assert_eq!(ErrorBit::try_from(0x00), Ok(ErrorBit::ErrNone));
+        assert_eq!(ErrorBit::try_from(0x07), Ok(ErrorBit::ErrAccess));
+        assert_eq!(ErrorBit::try_from(0x09), Err(0x09));
+        assert_eq!(u8::from(ErrorBit::ErrAccess), 0x07);
+        assert_eq!(CommunicationResult::from_status_error(0x00), Ok(()));
+        assert_eq!(
+            CommunicationResult::from_status_error(0x82),
+            Err(CommunicationResult::StatusError {
+                error: Ok(ErrorBit::ErrInstruction),
+                alert: true
+            })
+        );
+        assert_eq!(
+            CommunicationResult::from_status_error(0x89),
+            Err(CommunicationResult::StatusError {
+                error: Err(0x09),
+                alert: true
+            })
+        );
+    }
+
     #[test]
     fn clock() {
         let mut mock_uart = MockSerial::new();
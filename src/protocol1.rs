@@ -0,0 +1,614 @@
+use crate::packet_handler::push_params;
+use crate::packet_handler::BROADCAST_ID;
+use crate::packet_handler::MAX_PACKET_LEN;
+use crate::packet_handler::MAX_READ_ID_NUM;
+use crate::registry::DeviceRegistry;
+use crate::CommunicationResult;
+use crate::ControlTable;
+use crate::ErrorBit;
+use crate::HardwareErrorStatus;
+use crate::Instruction;
+use core::result::Result;
+use heapless::Vec;
+
+pub const PROTOCOL1_MAX_ID: u8 = 0xFD;
+
+pub enum Protocol1Packet {
+    Header0,
+    Header1,
+    Id,
+    Length,
+    Instruction,
+    Error,
+    Parameter0,
+}
+
+impl Protocol1Packet {
+    pub fn to_pos(&self) -> usize {
+        match self {
+            Protocol1Packet::Header0 => 0,
+            Protocol1Packet::Header1 => 1,
+            Protocol1Packet::Id => 2,
+            Protocol1Packet::Length => 3,
+            Protocol1Packet::Instruction => 4,
+            Protocol1Packet::Error => 4,
+            Protocol1Packet::Parameter0 => 5,
+        }
+    }
+}
+
+impl CommunicationResult {
+    /// Check the error byte of the Protocol 1.0 status packet.
+    /// Input voltage, overheating and overload are reported as HardwareError.
+    pub fn from_protocol1_status_error(id: u8, error: u8) -> Result<(), CommunicationResult> {
+        const ANGLE_LIMIT: u8 = 0x02;
+        const RANGE: u8 = 0x08;
+        const CHECKSUM: u8 = 0x10;
+        const INSTRUCTION: u8 = 0x40;
+        const HARDWARE: u8 = 0x25; // input voltage + overheating + overload
+
+        let error_bit = if error & INSTRUCTION != 0 {
+            ErrorBit::ErrInstruction
+        } else if error & CHECKSUM != 0 {
+            ErrorBit::ErrCRC
+        } else if error & RANGE != 0 {
+            ErrorBit::ErrDataRange
+        } else if error & ANGLE_LIMIT != 0 {
+            ErrorBit::ErrDataLimit
+        } else if error & HARDWARE != 0 {
+            return Err(CommunicationResult::HardwareError {
+                id,
+                status: HardwareErrorStatus::from_value(error & HARDWARE),
+            });
+        } else {
+            return Ok(());
+        };
+        Err(CommunicationResult::StatusError {
+            error: Ok(error_bit),
+            alert: error & HARDWARE != 0,
+        })
+    }
+}
+
+impl DeviceRegistry {
+    pub(crate) fn protocol1_read_packet(
+        &self,
+        id: u8,
+        data_name: ControlTable,
+        data_size: u16,
+    ) -> Result<Vec<u8, MAX_PACKET_LEN>, CommunicationResult> {
+        if id >= BROADCAST_ID || data_size > u8::MAX as u16 {
+            return Err(CommunicationResult::NotAvailable);
+        }
+        let address = match data_name.to_protocol1_address(&self.get_model(id)) {
+            Some(v) => v,
+            None => return Err(CommunicationResult::NotAvailable),
+        };
+        build_protocol1_packet(id, Instruction::Read, &[address, data_size as u8])
+    }
+
+    /// instruction: Write or RegWrite
+    pub(crate) fn protocol1_write_packet(
+        &self,
+        instruction: Instruction,
+        id: u8,
+        data_name: ControlTable,
+        data: &[u8],
+    ) -> Result<Vec<u8, MAX_PACKET_LEN>, CommunicationResult> {
+        if id >= BROADCAST_ID {
+            return Err(CommunicationResult::NotAvailable);
+        }
+        let address = match data_name.to_protocol1_address(&self.get_model(id)) {
+            Some(v) => v,
+            None => return Err(CommunicationResult::NotAvailable),
+        };
+        if data_name.to_protocol1_size() != Some(data.len() as u16) {
+            return Err(CommunicationResult::NotAvailable);
+        }
+
+        let mut params = Vec::<u8, MAX_PACKET_LEN>::new();
+        params.push(address).unwrap();
+        params.extend(data.iter().cloned());
+        build_protocol1_packet(id, instruction, &params)
+    }
+
+    pub(crate) fn protocol1_sync_write_packet(
+        &self,
+        id: &[u8],
+        data: &[u8],
+        data_name: ControlTable,
+        data_size: u16,
+    ) -> Result<Vec<u8, MAX_PACKET_LEN>, CommunicationResult> {
+        let address = self.get_ids_protocol1_address(id, data_name)?;
+        if data_size > u8::MAX as u16 || data.len() != id.len() * data_size as usize {
+            return Err(CommunicationResult::NotAvailable);
+        }
+
+        let mut params = Vec::<u8, MAX_PACKET_LEN>::new();
+        params.push(address).unwrap();
+        params.push(data_size as u8).unwrap();
+        for i in 0..id.len() {
+            push_params(&mut params, &[id[i]])?;
+            push_params(
+                &mut params,
+                &data[i * data_size as usize..(i + 1) * data_size as usize],
+            )?;
+        }
+        build_protocol1_packet(BROADCAST_ID, Instruction::SyncWrite, &params)
+    }
+
+    /// Bulk read is available only on MX series.
+    /// params: (id, data name, data length)
+    pub(crate) fn protocol1_bulk_read_packet(
+        &self,
+        params: &[(u8, ControlTable, u16)],
+    ) -> Result<Vec<u8, MAX_PACKET_LEN>, CommunicationResult> {
+        if params.len() > MAX_READ_ID_NUM
+            || params
+                .iter()
+                .any(|(id, _, _)| self.get_model(*id).is_ax_series())
+        {
+            return Err(CommunicationResult::NotAvailable);
+        }
+
+        let mut bulk_params = Vec::<u8, MAX_PACKET_LEN>::new();
+        bulk_params.push(0x00).unwrap();
+        for (id, data_name, data_length) in params {
+            let address = match data_name.to_protocol1_address(&self.get_model(*id)) {
+                Some(v) => v,
+                None => return Err(CommunicationResult::NotAvailable),
+            };
+            if *data_length > u8::MAX as u16 {
+                return Err(CommunicationResult::NotAvailable);
+            }
+            bulk_params.push(*data_length as u8).unwrap();
+            bulk_params.push(*id).unwrap();
+            bulk_params.push(address).unwrap();
+        }
+        build_protocol1_packet(BROADCAST_ID, Instruction::BulkRead, &bulk_params)
+    }
+
+    /// Sync write needs the same address on all the devices.
+    /// AX series lacks some items of MX series.
+    fn get_ids_protocol1_address(
+        &self,
+        ids: &[u8],
+        data_name: ControlTable,
+    ) -> Result<u8, CommunicationResult> {
+        let mut result = None;
+        for id in ids {
+            let address = match data_name.to_protocol1_address(&self.get_model(*id)) {
+                Some(v) => v,
+                None => return Err(CommunicationResult::NotAvailable),
+            };
+            if result.is_some() && result != Some(address) {
+                return Err(CommunicationResult::NotAvailable);
+            }
+            result = Some(address);
+        }
+        match result.or_else(|| data_name.to_protocol1_address(&self.get_model(BROADCAST_ID))) {
+            Some(v) => Ok(v),
+            None => Err(CommunicationResult::NotAvailable),
+        }
+    }
+}
+
+/// Build the Protocol 1.0 instruction packet with the header, the length and checksum.
+/// NotAvailable when the packet exceeds MAX_PACKET_LEN.
+pub(crate) fn build_protocol1_packet(
+    id: u8,
+    instruction: Instruction,
+    params: &[u8],
+) -> Result<Vec<u8, MAX_PACKET_LEN>, CommunicationResult> {
+    // header + id + length + (instruction + params + checksum)
+    if Protocol1Packet::Length.to_pos() + 1 + 2 + params.len() > MAX_PACKET_LEN {
+        return Err(CommunicationResult::NotAvailable);
+    }
+    let length = 2 + params.len() as u8; // instruction + params + checksum
+    let mut msg = Vec::<u8, MAX_PACKET_LEN>::new();
+
+    msg.extend([0x00; 2].iter().cloned()); // Header
+    msg.push(id).unwrap();
+    msg.push(length).unwrap();
+    msg.push(instruction as u8).unwrap();
+    msg.extend(params.iter().cloned());
+    finish_protocol1_packet(&mut msg)?;
+    Ok(msg)
+}
+
+/// Add header and checksum to the packet without checksum.
+pub(crate) fn finish_protocol1_packet(
+    msg: &mut Vec<u8, MAX_PACKET_LEN>,
+) -> Result<(), CommunicationResult> {
+    // make header
+    msg[Protocol1Packet::Header0.to_pos()] = 0xFF;
+    msg[Protocol1Packet::Header1.to_pos()] = 0xFF;
+
+    // add checksum
+    let checksum = calc_checksum_value(msg);
+    match msg.push(checksum) {
+        Ok(_) => Ok(()),
+        Err(_) => Err(CommunicationResult::TxError),
+    }
+}
+
+pub(crate) fn calc_checksum_value(msg: &[u8]) -> u8 {
+    let mut sum: u8 = 0;
+    // except header
+    for m in &msg[Protocol1Packet::Id.to_pos()..] {
+        sum = sum.wrapping_add(*m);
+    }
+    !sum
+}
+
+/// Find the Protocol 1.0 status packet at the beginning of the received bytes, dropping the bytes before its header.
+/// Ok(true) when the whole packet is received, Ok(false) when wait_length bytes are needed.
+pub(crate) fn find_protocol1_status_packet(
+    msg: &mut Vec<u8, MAX_PACKET_LEN>,
+    wait_length: &mut usize,
+) -> Result<bool, CommunicationResult> {
+    loop {
+        if msg.len() < *wait_length {
+            return Ok(false);
+        }
+        let mut idx = 0;
+        // find packet header
+        while idx < (msg.len() - 1) {
+            if msg[idx + Protocol1Packet::Header0.to_pos()] == 0xFF
+                && msg[idx + Protocol1Packet::Header1.to_pos()] == 0xFF
+            {
+                break;
+            }
+            idx += 1;
+        }
+
+        if idx != 0 {
+            // remove unnecessary packets
+            for s in 0..(msg.len() - idx) {
+                msg[s] = msg[idx + s];
+            }
+            msg.truncate(msg.len() - idx);
+            continue;
+        }
+
+        // found at the beginning of the packet
+        if msg[Protocol1Packet::Id.to_pos()] > PROTOCOL1_MAX_ID
+            || msg[Protocol1Packet::Length.to_pos()] < 2
+        {
+            // remove the first byte in the packet
+            for s in 0..msg.len() - 1 {
+                msg[s] = msg[s + 1];
+            }
+            msg.truncate(msg.len() - 1);
+            continue;
+        }
+        // re-calculate the exact length of the rx packet
+        let packet_length =
+            msg[Protocol1Packet::Length.to_pos()] as usize + Protocol1Packet::Length.to_pos() + 1;
+        if *wait_length != packet_length {
+            *wait_length = packet_length;
+            continue;
+        }
+
+        // verify checksum
+        if calc_checksum_value(&msg[..msg.len() - 1]) == msg[msg.len() - 1] {
+            return Ok(true);
+        } else {
+            return Err(CommunicationResult::RxCRCError);
+        }
+    }
+}
+
+/// Check the Protocol 1.0 status packet from the device and return its parameters.
+pub(crate) fn parse_protocol1_status_packet(
+    status: &[u8],
+    id: u8,
+    data_length: u16,
+) -> Result<Vec<u8, MAX_PACKET_LEN>, CommunicationResult> {
+    // header + id + length + err + param + checksum
+    // id check
+    if status[Protocol1Packet::Id.to_pos()] != id {
+        return Err(CommunicationResult::SomethingWentWrong);
+    }
+    // error check comes first, because status with error may have no param
+    match CommunicationResult::from_protocol1_status_error(
+        id,
+        status[Protocol1Packet::Error.to_pos()],
+    ) {
+        Ok(_) => {}
+        Err(e) => return Err(e),
+    }
+    // data length check
+    if (status[Protocol1Packet::Length.to_pos()] as u16).checked_sub(2) != Some(data_length) {
+        return Err(CommunicationResult::SomethingWentWrong);
+    }
+
+    let mut data = Vec::<u8, MAX_PACKET_LEN>::new();
+    data.extend(
+        status[Protocol1Packet::Parameter0.to_pos()
+            ..(Protocol1Packet::Parameter0.to_pos() + data_length as usize)]
+            .iter()
+            .cloned(),
+    );
+    Ok(data)
+}
+
+#[cfg(test)]
+#[allow(clippy::assertions_on_constants, clippy::bool_assert_comparison)]
+mod tests {
+    use crate::packet_handler::CommunicationResult;
+    use crate::packet_handler::ErrorBit;
+    use crate::packet_handler::BROADCAST_ID;
+    use crate::packet_handler::MAX_ID;
+    use crate::protocol1::calc_checksum_value;
+    use crate::tests::MockClock;
+    use crate::tests::MockSerial;
+    use crate::ControlTable;
+    use crate::DynamixelControl;
+    use crate::DynamixelModel;
+    use crate::HardwareError;
+    use crate::ProtocolVersion;
+
+    #[test]
+    fn checksum() {
+        // Read Present Position of ID1
+        let msg = [0xFF, 0xFF, 0x01, 0x04, 0x02, 0x24, 0x02];
+        assert_eq!(calc_checksum_value(&msg), 0xD2);
+    }
+
+    #[test]
+    fn ping() {
+        let mut mock_uart = MockSerial::new();
+        let mock_clock = MockClock::new();
+        let mut dxl = DynamixelControl::new(&mut mock_uart, &mock_clock, 57600);
+        dxl.set_protocol_version(ProtocolVersion::V1);
+        let result = dxl.ping(1);
+        assert_eq!(result, Ok((0x000C, 0x18)));
+        assert_eq!(mock_uart.rx_buf[..6], [0xFF, 0xFF, 0x01, 0x02, 0x01, 0xFB]);
+        assert_eq!(
+            mock_uart.rx_buf[6..],
+            [0xFF, 0xFF, 0x01, 0x04, 0x02, 0x00, 0x03, 0xF5]
+        );
+    }
+
+    #[test]
+    fn read() {
+        let mut mock_uart = MockSerial::new();
+        let mock_clock = MockClock::new();
+        let mut dxl = DynamixelControl::new(&mut mock_uart, &mock_clock, 57600);
+        dxl.set_protocol_version(ProtocolVersion::V1);
+        let result = dxl.read_2byte(1, ControlTable::PresentPosition);
+        assert_eq!(result, Ok(512));
+        assert_eq!(
+            mock_uart.rx_buf[..],
+            [0xFF, 0xFF, 0x01, 0x04, 0x02, 0x24, 0x02, 0xD2]
+        );
+    }
+
+    #[test]
+    fn get_present_position() {
+        let mut mock_uart = MockSerial::new();
+        let mock_clock = MockClock::new();
+        let mut dxl = DynamixelControl::new(&mut mock_uart, &mock_clock, 57600);
+        dxl.set_protocol_version(ProtocolVersion::V1);
+        // Present Position is 2 bytes, so Present Speed is not read together.
+        assert_eq!(dxl.read_4byte(1, ControlTable::PresentPosition), Ok(512));
+        assert_eq!(
+            dxl.send_4byte_read_packet(1, ControlTable::PresentPosition),
+            Err(CommunicationResult::NotAvailable)
+        );
+        // Home position of AX series is 512
+        dxl.ping(1).unwrap();
+        assert_eq!(dxl.get_model(1), DynamixelModel::Ax12A);
+        assert_eq!(dxl.get_present_position(1), Ok(0.0));
+        assert_eq!(
+            mock_uart.rx_buf[..8],
+            [0xFF, 0xFF, 0x01, 0x04, 0x02, 0x24, 0x02, 0xD2]
+        );
+        assert_eq!(
+            mock_uart.rx_buf[22..],
+            [0xFF, 0xFF, 0x01, 0x04, 0x02, 0x24, 0x02, 0xD2]
+        );
+    }
+
+    #[test]
+    fn write() {
+        let mut mock_uart = MockSerial::new();
+        let mock_clock = MockClock::new();
+        let mut dxl = DynamixelControl::new(&mut mock_uart, &mock_clock, 57600);
+        dxl.set_protocol_version(ProtocolVersion::V1);
+        let result = dxl.write_1byte(1, ControlTable::TorqueEnable, 1);
+        assert_eq!(result, Ok(()));
+        // Not in the control table of Protocol 1.0
+        let result = dxl.write_1byte(1, ControlTable::OperatingMode, 3);
+        assert_eq!(result, Err(CommunicationResult::NotAvailable));
+        assert_eq!(
+            mock_uart.rx_buf[..],
+            [0xFF, 0xFF, 0x01, 0x04, 0x03, 0x18, 0x01, 0xDE]
+        );
+    }
+
+    #[test]
+    fn status_error() {
+        let mut mock_uart = MockSerial::new();
+        let mock_clock = MockClock::new();
+        let mut dxl = DynamixelControl::new(&mut mock_uart, &mock_clock, 57600);
+        dxl.set_protocol_version(ProtocolVersion::V1);
+        let result = dxl.write_2byte(2, ControlTable::GoalPosition, 0x0200);
+        assert_eq!(
+            result,
+            Err(CommunicationResult::StatusError {
+                error: Ok(ErrorBit::ErrDataRange),
+                alert: false
+            })
+        );
+        match dxl.read_1byte(2, ControlTable::PresentTemperature) {
+            Err(CommunicationResult::HardwareError { id, status }) => {
+                assert_eq!(id, 2);
+                assert_eq!(status.contains(HardwareError::Overheating), true);
+                assert_eq!(status.contains(HardwareError::Overload), true);
+            }
+            _ => assert!(false),
+        }
+    }
+
+    #[test]
+    fn sync_write() {
+        let mut mock_uart = MockSerial::new();
+        let mock_clock = MockClock::new();
+        let mut dxl = DynamixelControl::new(&mut mock_uart, &mock_clock, 57600);
+        dxl.set_protocol_version(ProtocolVersion::V1);
+        let result = dxl.send_sync_write_packet(
+            &[1, 2],
+            &[0x00, 0x02, 0x00, 0x01],
+            ControlTable::GoalPosition,
+            2,
+        );
+        assert_eq!(result, Ok(()));
+        // AX series has no PID gains
+        dxl.set_model(2, DynamixelModel::Ax12A).unwrap();
+        let result =
+            dxl.send_sync_write_packet(&[1, 2], &[0x20, 0x20], ControlTable::PositionPGain, 1);
+        assert_eq!(result, Err(CommunicationResult::NotAvailable));
+        assert_eq!(
+            mock_uart.rx_buf[..],
+            [0xFF, 0xFF, 0xFE, 0x0A, 0x83, 0x1E, 0x02, 0x01, 0x00, 0x02, 0x02, 0x00, 0x01, 0x4E]
+        );
+    }
+
+    #[test]
+    fn bulk_read() {
+        let mut mock_uart = MockSerial::new();
+        let mock_clock = MockClock::new();
+        let mut dxl = DynamixelControl::new(&mut mock_uart, &mock_clock, 57600);
+        dxl.set_protocol_version(ProtocolVersion::V1);
+        let result = dxl
+            .bulk_read(&[
+                (1, ControlTable::PresentPosition, 2),
+                (2, ControlTable::PresentTemperature, 1),
+            ])
+            .unwrap();
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[0].0, 1);
+        assert_eq!(result[0].1.as_ref().unwrap()[..], [0x00, 0x02]);
+        assert_eq!(result[1].0, 2);
+        assert_eq!(result[1].1.as_ref().unwrap()[..], [0x28]);
+        // AX series has no bulk read
+        dxl.set_model(2, DynamixelModel::Ax12A).unwrap();
+        let result = dxl.bulk_read(&[(2, ControlTable::PresentTemperature, 1)]);
+        assert_eq!(result, Err(CommunicationResult::NotAvailable));
+        assert_eq!(
+            mock_uart.rx_buf[..],
+            [0xFF, 0xFF, 0xFE, 0x09, 0x92, 0x00, 0x02, 0x01, 0x24, 0x01, 0x02, 0x2B, 0x11]
+        );
+    }
+
+    #[test]
+    fn id_protocol_version() {
+        let mut mock_uart = MockSerial::new();
+        let mock_clock = MockClock::new();
+        let mut dxl = DynamixelControl::new(&mut mock_uart, &mock_clock, 57600);
+        assert_eq!(dxl.set_id_protocol_version(1, ProtocolVersion::V1), Ok(()));
+        assert_eq!(
+            dxl.set_id_protocol_version(BROADCAST_ID, ProtocolVersion::V1),
+            Err(CommunicationResult::NotAvailable)
+        );
+        assert_eq!(dxl.get_id_protocol_version(1), ProtocolVersion::V1);
+        assert_eq!(dxl.get_id_protocol_version(3), ProtocolVersion::V2);
+        // Every valid ID can be registered
+        for id in 10..=MAX_ID {
+            assert_eq!(dxl.set_id_protocol_version(id, ProtocolVersion::V2), Ok(()));
+        }
+        assert_eq!(
+            dxl.set_id_protocol_version(MAX_ID + 1, ProtocolVersion::V1),
+            Err(CommunicationResult::NotAvailable)
+        );
+        assert_eq!(dxl.set_id_protocol_version(1, ProtocolVersion::V1), Ok(()));
+        assert_eq!(dxl.get_id_protocol_version(100), ProtocolVersion::V2);
+
+        let result = dxl.read_2byte(1, ControlTable::PresentPosition);
+        assert_eq!(result, Ok(512));
+        let result = dxl.write_1byte(3, ControlTable::TorqueEnable, 1);
+        assert_eq!(result, Ok(()));
+        assert_eq!(
+            mock_uart.rx_buf[..8],
+            [0xFF, 0xFF, 0x01, 0x04, 0x02, 0x24, 0x02, 0xD2]
+        );
+        assert_eq!(
+            mock_uart.rx_buf[8..],
+            [0xFF, 0xFF, 0xFD, 0x00, 0x03, 0x06, 0x00, 0x03, 0x40, 0x00, 0x01, 0xF8, 0xE4]
+        );
+    }
+
+    #[test]
+    fn mixed_protocol_sync_write() {
+        let mut mock_uart = MockSerial::new();
+        let mock_clock = MockClock::new();
+        let mut dxl = DynamixelControl::new(&mut mock_uart, &mock_clock, 57600);
+        dxl.set_id_protocol_version(1, ProtocolVersion::V1).unwrap();
+        let result =
+            dxl.send_sync_write_packet(&[1, 3], &[0x01, 0x01], ControlTable::TorqueEnable, 1);
+        assert_eq!(result, Ok(()));
+        assert_eq!(
+            mock_uart.rx_buf[..16],
+            [
+                0xFF, 0xFF, 0xFD, 0x00, 0xFE, 0x09, 0x00, 0x83, 0x40, 0x00, 0x01, 0x00, 0x03, 0x01,
+                0x1E, 0x53
+            ]
+        );
+        assert_eq!(
+            mock_uart.rx_buf[16..],
+            [0xFF, 0xFF, 0xFE, 0x06, 0x83, 0x18, 0x01, 0x01, 0x01, 0x5D]
+        );
+    }
+
+    #[test]
+    fn mixed_protocol_bulk_read() {
+        let mut mock_uart = MockSerial::new();
+        let mock_clock = MockClock::new();
+        let mut dxl = DynamixelControl::new(&mut mock_uart, &mock_clock, 57600);
+        dxl.set_id_protocol_version(1, ProtocolVersion::V1).unwrap();
+        let params = [
+            (1, ControlTable::PresentPosition, 2),
+            (3, ControlTable::PresentPosition, 4),
+        ];
+        // Separate packets are needed.
+        assert_eq!(
+            dxl.send_bulk_read_packet(&params),
+            Err(CommunicationResult::NotAvailable)
+        );
+        let result = dxl.bulk_read(&params).unwrap();
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[0].0, 1);
+        assert_eq!(result[0].1.as_ref().unwrap()[..], [0x00, 0x02]);
+        assert_eq!(result[1].0, 3);
+        assert_eq!(result[1].1.as_ref().unwrap()[..], [0xA6, 0x00, 0x00, 0x00]);
+        assert_eq!(
+            mock_uart.rx_buf[..15],
+            [
+                0xFF, 0xFF, 0xFD, 0x00, 0xFE, 0x08, 0x00, 0x92, 0x03, 0x84, 0x00, 0x04, 0x00, 0xED,
+                0xBA
+            ]
+        );
+        assert_eq!(
+            mock_uart.rx_buf[15..],
+            [0xFF, 0xFF, 0xFE, 0x06, 0x92, 0x00, 0x02, 0x01, 0x24, 0x42]
+        );
+    }
+
+    #[test]
+    fn mixed_protocol_broadcast_action() {
+        let mut mock_uart = MockSerial::new();
+        let mock_clock = MockClock::new();
+        let mut dxl = DynamixelControl::new(&mut mock_uart, &mock_clock, 57600);
+        dxl.set_id_protocol_version(1, ProtocolVersion::V1).unwrap();
+        assert_eq!(dxl.action(BROADCAST_ID), Ok(()));
+        assert_eq!(
+            mock_uart.rx_buf[..],
+            [
+                0xFF, 0xFF, 0xFD, 0x00, 0xFE, 0x03, 0x00, 0x05, 0x2A, 0xC2, 0xFF, 0xFF, 0xFE, 0x02,
+                0x05, 0xFA
+            ]
+        );
+    }
+}
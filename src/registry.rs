@@ -0,0 +1,315 @@
+use crate::packet_handler::BROADCAST_ID;
+use crate::packet_handler::MAX_DEVICE_NUM;
+use crate::Access;
+use crate::CommunicationResult;
+use crate::ControlTable;
+use crate::DeviceLimits;
+use crate::DynamixelModel;
+use crate::Instruction;
+use crate::MemoryArea;
+use crate::ProtocolVersion;
+use core::result::Result;
+
+/// Settings registered for each ID.
+#[derive(Clone, Copy, Default)]
+struct DeviceState {
+    protocol_version: Option<ProtocolVersion>,
+    model: Option<DynamixelModel>,
+    torque_enabled: Option<bool>,
+    limits: Option<DeviceLimits>,
+}
+
+/// Devices on the bus, shared by DynamixelControl and AsyncDynamixelControl.
+/// Packets are built from and checked against it without I/O.
+pub(crate) struct DeviceRegistry {
+    pub(crate) protocol_version: ProtocolVersion,
+    devices: [DeviceState; MAX_DEVICE_NUM],
+    pub(crate) range_check: bool,
+}
+
+impl DeviceRegistry {
+    pub(crate) fn new() -> Self {
+        Self {
+            protocol_version: ProtocolVersion::V2,
+            devices: [DeviceState::default(); MAX_DEVICE_NUM],
+            range_check: false,
+        }
+    }
+
+    pub(crate) fn set_id_protocol_version(
+        &mut self,
+        id: u8,
+        version: ProtocolVersion,
+    ) -> Result<(), CommunicationResult> {
+        self.device_mut(id)?.protocol_version = Some(version);
+        Ok(())
+    }
+
+    pub(crate) fn get_id_protocol_version(&self, id: u8) -> ProtocolVersion {
+        match self.device(id).and_then(|d| d.protocol_version) {
+            Some(v) => v,
+            None => self.protocol_version,
+        }
+    }
+
+    pub(crate) fn set_model(
+        &mut self,
+        id: u8,
+        model: DynamixelModel,
+    ) -> Result<(), CommunicationResult> {
+        self.device_mut(id)?.model = Some(model);
+        Ok(())
+    }
+
+    pub(crate) fn get_model(&self, id: u8) -> DynamixelModel {
+        self.device(id).and_then(|d| d.model).unwrap_or_default()
+    }
+
+    /// Register the model answered by ping for control table and unit conversion.
+    pub(crate) fn register_model(&mut self, id: u8, model_number: u16) {
+        let model = DynamixelModel::from_model_number(model_number);
+        if let (Some(device), Some(model)) = (self.devices.get_mut(id as usize), model) {
+            device.model = Some(model);
+        }
+    }
+
+    fn device(&self, id: u8) -> Option<&DeviceState> {
+        self.devices.get(id as usize)
+    }
+
+    /// Every ID up to MAX_ID can be registered.
+    fn device_mut(&mut self, id: u8) -> Result<&mut DeviceState, CommunicationResult> {
+        match self.devices.get_mut(id as usize) {
+            Some(v) => Ok(v),
+            None => Err(CommunicationResult::NotAvailable),
+        }
+    }
+
+    pub(crate) fn cache_limits(&mut self, id: u8, limits: DeviceLimits) {
+        if let Ok(device) = self.device_mut(id) {
+            device.limits = Some(limits);
+        }
+    }
+
+    pub(crate) fn get_limits(&self, id: u8) -> Option<DeviceLimits> {
+        self.device(id).and_then(|d| d.limits)
+    }
+
+    pub(crate) fn clear_limits(&mut self, id: u8) {
+        if let Ok(device) = self.device_mut(id) {
+            device.limits = None;
+        }
+    }
+
+    /// Protocol version shared by all the IDs, or None for mixed protocols.
+    pub(crate) fn get_ids_protocol_version(
+        &self,
+        ids: impl Iterator<Item = u8>,
+    ) -> Option<ProtocolVersion> {
+        let mut result = None;
+        for id in ids {
+            let version = self.get_id_protocol_version(id);
+            if result.is_some() && result != Some(version) {
+                return None;
+            }
+            result = Some(version);
+        }
+        match result {
+            Some(v) => Some(v),
+            None => Some(self.protocol_version),
+        }
+    }
+
+    pub(crate) fn is_protocol_version_used(&self, version: ProtocolVersion) -> bool {
+        self.protocol_version == version
+            || self
+                .devices
+                .iter()
+                .any(|d| d.protocol_version == Some(version))
+    }
+
+    /// Address of the item on the model of the device.
+    pub(crate) fn get_address(
+        &self,
+        id: u8,
+        data_name: ControlTable,
+    ) -> Result<u16, CommunicationResult> {
+        let model = self.get_model(id);
+        if !data_name.is_supported(&model) {
+            return Err(CommunicationResult::NotAvailable);
+        }
+        Ok(data_name.to_address(&model))
+    }
+
+    /// Sync instructions need the same address on all the devices.
+    pub(crate) fn get_ids_address(
+        &self,
+        ids: &[u8],
+        data_name: ControlTable,
+    ) -> Result<u16, CommunicationResult> {
+        let mut result = None;
+        for id in ids {
+            let address = self.get_address(*id, data_name)?;
+            if result.is_some() && result != Some(address) {
+                return Err(CommunicationResult::NotAvailable);
+            }
+            result = Some(address);
+        }
+        match result {
+            Some(v) => Ok(v),
+            None => self.get_address(BROADCAST_ID, data_name),
+        }
+    }
+
+    /// Size to read the item as data_size bytes of Protocol 2.0 control table.
+    /// Protocol 1.0 items are smaller, e.g. Present Position is 2 bytes,
+    /// so the Protocol 1.0 size is read not to read the next item together.
+    pub(crate) fn get_read_size(
+        &self,
+        id: u8,
+        data_name: ControlTable,
+        data_size: u16,
+    ) -> Result<u16, CommunicationResult> {
+        if self.get_id_protocol_version(id) == ProtocolVersion::V2 {
+            return Ok(data_size);
+        }
+        match data_name.to_protocol1_size() {
+            Some(v) if v <= data_size => Ok(v),
+            _ => Err(CommunicationResult::NotAvailable),
+        }
+    }
+
+    /// Refuse the write which the device would reject with Access error.
+    /// EEPROM items are refused only when Torque Enable written by this driver is on.
+    pub(crate) fn check_writable(
+        &self,
+        id: u8,
+        data_name: ControlTable,
+    ) -> Result<(), CommunicationResult> {
+        if data_name.to_access() == Access::ReadOnly {
+            return Err(CommunicationResult::ReadOnly);
+        }
+        let is_eeprom = match self.get_id_protocol_version(id) {
+            ProtocolVersion::V1 => match data_name.to_protocol1_address(&self.get_model(id)) {
+                Some(v) => v < 24,
+                None => false,
+            },
+            ProtocolVersion::V2 => data_name.to_area(&self.get_model(id)) == MemoryArea::EEPROM,
+        };
+        if is_eeprom && self.device(id).and_then(|d| d.torque_enabled) == Some(true) {
+            return Err(CommunicationResult::TorqueEnabled);
+        }
+        Ok(())
+    }
+
+    /// Validate the value enabled by set_range_check.
+    /// Protocol 1.0 devices are not validated, because the ranges are for Protocol 2.0 control table.
+    /// Ok(Some(value)) when the value is also limited by the limits of the device.
+    pub(crate) fn check_range(
+        &self,
+        id: u8,
+        data_name: ControlTable,
+        data: &[u8],
+    ) -> Result<Option<i64>, CommunicationResult> {
+        if !self.range_check || self.get_id_protocol_version(id) == ProtocolVersion::V1 {
+            return Ok(None);
+        }
+        let (min, max) = match data_name.to_range(&self.get_model(id)) {
+            Some(v) => v,
+            None => return Ok(None),
+        };
+        let signed = min < 0;
+        let value: i64 = match data.len() {
+            1 if signed => data[0] as i8 as i64,
+            1 => data[0] as i64,
+            2 if signed => i16::from_le_bytes([data[0], data[1]]) as i64,
+            2 => u16::from_le_bytes([data[0], data[1]]) as i64,
+            4 if signed => i32::from_le_bytes([data[0], data[1], data[2], data[3]]) as i64,
+            4 => u32::from_le_bytes([data[0], data[1], data[2], data[3]]) as i64,
+            _ => return Ok(None), // Size is checked when building the packet
+        };
+        if value < min as i64 || value > max as i64 {
+            return Err(CommunicationResult::OutOfRange);
+        }
+        match data_name {
+            ControlTable::GoalPosition
+            | ControlTable::GoalCurrent
+            | ControlTable::GoalVelocity
+            | ControlTable::GoalPWM => Ok(Some(value)),
+            _ => Ok(None),
+        }
+    }
+
+    /// Cache Torque Enable and drop the limits changed by the write instruction sent to the device.
+    pub(crate) fn cache_write(
+        &mut self,
+        instruction: Instruction,
+        id: u8,
+        data_name: ControlTable,
+        data: &[u8],
+    ) {
+        if data_name.to_area(&self.get_model(id)) == MemoryArea::EEPROM {
+            self.clear_limits(id);
+        }
+        match instruction {
+            Instruction::Write => self.cache_torque_enable(id, data_name, data),
+            // Torque Enable is unknown until action is received.
+            _ if data_name == ControlTable::TorqueEnable => self.forget_torque_enable(id),
+            _ => {}
+        }
+    }
+
+    /// Cache Torque Enable sent to the device.
+    pub(crate) fn cache_torque_enable(&mut self, id: u8, data_name: ControlTable, data: &[u8]) {
+        if data_name != ControlTable::TorqueEnable || data.is_empty() {
+            return;
+        }
+        if let Ok(device) = self.device_mut(id) {
+            device.torque_enabled = Some(data[0] != 0);
+        }
+    }
+
+    pub(crate) fn cache_sync_torque_enable(
+        &mut self,
+        id: &[u8],
+        data: &[u8],
+        data_name: ControlTable,
+        data_size: u16,
+    ) {
+        if data_size == 0 {
+            return;
+        }
+        for (i, chunk) in id.iter().zip(data.chunks(data_size as usize)) {
+            self.cache_torque_enable(*i, data_name, chunk);
+        }
+    }
+
+    /// Torque is disabled by reboot and factory reset, but it is unknown until written again.
+    pub(crate) fn forget_torque_enable(&mut self, id: u8) {
+        for (device_id, device) in self.devices.iter_mut().enumerate() {
+            if id == BROADCAST_ID || device_id == id as usize {
+                device.torque_enabled = None;
+            }
+        }
+    }
+}
+
+/// Validate the value checked by DeviceRegistry::check_range against the limits of the device.
+pub(crate) fn check_limits(
+    data_name: ControlTable,
+    value: i64,
+    limits: &DeviceLimits,
+) -> Result<(), CommunicationResult> {
+    let (min, max) = match data_name {
+        ControlTable::GoalPosition => (limits.min_position, limits.max_position),
+        ControlTable::GoalCurrent => (limits.current.map(|v| -v), limits.current),
+        ControlTable::GoalVelocity => (limits.velocity.map(|v| -v), limits.velocity),
+        _ => (limits.pwm.map(|v| -v), limits.pwm),
+    };
+    let below = matches!(min, Some(v) if value < v as i64);
+    let above = matches!(max, Some(v) if value > v as i64);
+    if below || above {
+        return Err(CommunicationResult::OutOfRange);
+    }
+    Ok(())
+}
@@ -0,0 +1,134 @@
+//! Interface and Clock for desktop applications, enabled by the `std` feature.
+//!
+//! ```no_run
+//! use dynamixel_rs::serial_port::{SerialPortInterface, StdClock};
+//! use dynamixel_rs::DynamixelControl;
+//!
+//! let mut port = SerialPortInterface::open("/dev/ttyUSB0", 1_000_000).unwrap();
+//! let clock = StdClock::new();
+//! let mut dxl = DynamixelControl::new(&mut port, &clock, 1_000_000);
+//! ```
+
+use crate::Clock;
+use crate::Interface;
+use core::time::Duration;
+use serialport::{ClearBuffer, DataBits, FlowControl, Parity, SerialPort, StopBits};
+use std::boxed::Box;
+use std::format;
+use std::io::{Read, Write};
+use std::path::Path;
+use std::string::String;
+use std::time::Instant;
+
+pub struct SerialPortInterface {
+    port: Box<dyn SerialPort>,
+}
+
+impl SerialPortInterface {
+    /// Open the port with 8N1 and without flow control, then set low latency mode.
+    pub fn open(path: &str, baudrate: u32) -> Result<Self, serialport::Error> {
+        let port = serialport::new(path, baudrate)
+            .data_bits(DataBits::Eight)
+            .parity(Parity::None)
+            .stop_bits(StopBits::One)
+            .flow_control(FlowControl::None)
+            .timeout(Duration::ZERO) // DynamixelControl polls and handles the timeout.
+            .open()?;
+        // Low latency mode needs the permission to write sysfs, so the port is usable without it.
+        let _ = set_low_latency(path);
+        Ok(Self { port })
+    }
+
+    pub fn from_port(port: Box<dyn SerialPort>) -> Self {
+        Self { port }
+    }
+
+    pub fn set_baudrate(&mut self, baudrate: u32) -> Result<(), serialport::Error> {
+        self.port.set_baud_rate(baudrate)
+    }
+}
+
+impl Interface for SerialPortInterface {
+    fn write_byte(&mut self, data: u8) {
+        self.write_bytes(&[data]);
+    }
+    fn write_bytes(&mut self, data: &[u8]) {
+        let _ = self.port.write_all(data);
+        let _ = self.port.flush();
+    }
+    fn read_byte(&mut self) -> Option<u8> {
+        let mut buf = [0u8; 1];
+        match self.read_bytes(&mut buf) {
+            Some(1) => Some(buf[0]),
+            _ => None,
+        }
+    }
+    fn read_bytes(&mut self, buf: &mut [u8]) -> Option<usize> {
+        // Timed out if nothing is received yet
+        self.port.read(buf).ok()
+    }
+    fn clear_read_buf(&mut self) {
+        let _ = self.port.clear(ClearBuffer::Input);
+    }
+}
+
+/// Set the latency timer of USB serial converters (e.g. U2D2) to 1 [ms] from 16 [ms].
+pub fn set_low_latency(path: &str) -> std::io::Result<()> {
+    std::fs::write(latency_timer_path(path), "1")
+}
+
+fn latency_timer_path(path: &str) -> String {
+    let name = match Path::new(path).file_name() {
+        Some(v) => v.to_string_lossy(),
+        None => path.into(),
+    };
+    format!("/sys/bus/usb-serial/devices/{}/latency_timer", name)
+}
+
+/// Clock counting from its creation.
+pub struct StdClock {
+    start: Instant,
+}
+
+impl StdClock {
+    pub fn new() -> Self {
+        Self {
+            start: Instant::now(),
+        }
+    }
+}
+
+impl Default for StdClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for StdClock {
+    fn get_current_time(&self) -> Duration {
+        self.start.elapsed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::serial_port::latency_timer_path;
+    use crate::serial_port::StdClock;
+    use crate::Clock;
+
+    #[test]
+    fn latency_timer() {
+        assert_eq!(
+            latency_timer_path("/dev/ttyUSB0"),
+            "/sys/bus/usb-serial/devices/ttyUSB0/latency_timer"
+        );
+    }
+
+    #[test]
+    fn std_clock() {
+        let clock = StdClock::new();
+        let t0 = clock.get_current_time();
+        std::thread::sleep(core::time::Duration::from_millis(1));
+        assert!(clock.get_current_time() > t0);
+    }
+}
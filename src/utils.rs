@@ -20,3 +20,17 @@ impl DegRad<f64> for f64 {
         self * 180.0 / core::f64::consts::PI
     }
 }
+
+/// Time to send 8 bits of a byte [usec], rounded up.
+pub(crate) fn calc_tx_time_per_byte(baudrate: u32) -> u64 {
+    ((1_000_000.0 * 8.0 + (baudrate as f32 - 1.0)) / baudrate as f32) as u64
+}
+
+/// Round half away from zero, since f32::round is not in core.
+pub(crate) fn round_to_i32(value: f32) -> i32 {
+    if value < 0.0 {
+        (value - 0.5) as i32
+    } else {
+        (value + 0.5) as i32
+    }
+}